@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use t3elric_engine::{
+    parser::{Board, BoardState, Player, TimeSetting},
+    scorer::{GreedyScorer, Scorer},
+};
+
+/// A middle game on a 9x9 board, five in a row to win.
+fn middle_game() -> BoardState {
+    BoardState::new(
+        Player::X,
+        "9_/9_/9_/3_x5_/3_ox4_/4_o4_/9_/9_/9_"
+            .parse::<Board>()
+            .unwrap(),
+        TimeSetting::Infinite,
+        Some(5),
+    )
+}
+
+fn check_win(c: &mut Criterion) {
+    let board_state = middle_game();
+    c.bench_function("check_win", |b| {
+        b.iter(|| black_box(&board_state).check_win(Player::X))
+    });
+}
+
+fn score(c: &mut Criterion) {
+    let scorer = GreedyScorer::default();
+    let board_state = middle_game();
+    c.bench_function("GreedyScorer::score", |b| {
+        b.iter(|| scorer.score(&mut black_box(board_state.clone())))
+    });
+}
+
+fn get_possible_moves(c: &mut Criterion) {
+    let board_state = middle_game();
+    c.bench_function("get_possible_moves", |b| {
+        b.iter(|| black_box(&board_state).get_possible_moves())
+    });
+}
+
+criterion_group!(benches, check_win, score, get_possible_moves);
+criterion_main!(benches);
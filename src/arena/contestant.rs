@@ -0,0 +1,177 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{channel, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    parser::{BoardState, MultiMove},
+    solver::Solver,
+};
+
+/// A player in a match, asked for its turn in every position it has to move in.
+pub trait Contestant {
+    fn name(&self) -> &str;
+
+    fn play(&mut self, board_state: &BoardState) -> Result<MultiMove, String>;
+}
+
+/// Plays with one of the engine's own solvers, in-process.
+pub struct SolverContestant {
+    name: String,
+    solver: Box<dyn Solver>,
+}
+
+impl SolverContestant {
+    pub fn new(name: &str, solver: Box<dyn Solver>) -> Self {
+        Self {
+            name: name.to_string(),
+            solver,
+        }
+    }
+}
+
+impl Contestant for SolverContestant {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn play(&mut self, board_state: &BoardState) -> Result<MultiMove, String> {
+        self.solver
+            .solve_turn(&mut board_state.clone())
+            .0
+            .ok_or_else(|| format!("{} found no move", self.name))
+    }
+}
+
+/// How long an engine has to answer each step of the handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Plays with an external ST3P engine, run as a subprocess and spoken to over its
+/// stdin and stdout. Its replies are read on a thread of their own, so that an engine that
+/// does not answer in time can be given up on.
+pub struct EngineContestant {
+    name: String,
+    process: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    /// How the handshake went; an engine that failed it forfeits its first game.
+    handshake: Result<(), String>,
+}
+
+impl EngineContestant {
+    /// Starts the engine a command line such as `./t3elric-engine --gravity` runs.
+    pub fn from_command(command: &str) -> Result<Self, String> {
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words
+            .next()
+            .ok_or_else(|| "An engine needs a command to run.".to_string())?;
+        let args: Vec<String> = words.collect();
+        Self::start(&program, &args)
+    }
+
+    /// Starts `program` with `args` and shakes hands with it, taking the engine's name from
+    /// its `identify` reply where it gives one. Only fails if the program cannot be run.
+    pub fn start(program: &str, args: &[String]) -> Result<Self, String> {
+        let mut process = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|error| format!("Could not start {}: {}", program, error))?;
+        let stdin = process.stdin.take().ok_or("Engine has no stdin")?;
+        let stdout = process.stdout.take().ok_or("Engine has no stdout")?;
+        let (sender, lines) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            name: program.to_string(),
+            process,
+            stdin,
+            lines,
+            handshake: Ok(()),
+        };
+        engine.handshake = engine
+            .shake_hands()
+            .map_err(|error| format!("{} during the handshake", error));
+        Ok(engine)
+    }
+
+    fn shake_hands(&mut self) -> Result<(), String> {
+        self.send("st3p version 1")?;
+        self.read_until(|line| line == "st3p version 1 ok")?;
+        self.send("identify")?;
+        self.read_until(|line| line == "identify ok")
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|error| format!("Could not write to {}: {}", self.name, error))
+    }
+
+    /// Waits `timeout` for the engine's next line, or for as long as it takes without one.
+    fn read_line(&mut self, timeout: Option<Duration>) -> Result<String, String> {
+        let line = match timeout {
+            Some(timeout) => self.lines.recv_timeout(timeout),
+            None => self
+                .lines
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match line {
+            Ok(line) => Ok(line.trim().to_string()),
+            Err(RecvTimeoutError::Timeout) => Err(format!("{} ran out of time", self.name)),
+            Err(RecvTimeoutError::Disconnected) => Err(format!("{} closed its output", self.name)),
+        }
+    }
+
+    /// Reads lines up to the one `done` accepts, picking up the engine's name on the way.
+    fn read_until(&mut self, done: impl Fn(&str) -> bool) -> Result<(), String> {
+        loop {
+            let line = self.read_line(Some(HANDSHAKE_TIMEOUT))?;
+            if done(&line) {
+                return Ok(());
+            }
+            if let Some(name) = line.strip_prefix("identify name ") {
+                self.name = name.to_string();
+            }
+        }
+    }
+}
+
+impl Contestant for EngineContestant {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn play(&mut self, board_state: &BoardState) -> Result<MultiMove, String> {
+        self.handshake.clone()?;
+        // Drop a late reply to a move the engine already ran out of time on.
+        while self.lines.try_recv().is_ok() {}
+        self.send(&format!("move {}", board_state))?;
+        let timeout = board_state
+            .time_setting
+            .limit()
+            .map(|limit| Duration::from_millis(limit.0 as u64));
+        let reply = self.read_line(timeout)?;
+        reply
+            .parse::<MultiMove>()
+            .map_err(|error| format!("{} replied {}: {}", self.name, reply, error))
+    }
+}
+
+impl Drop for EngineContestant {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.process.wait();
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Display;
+
+use crate::{
+    parser::{BoardState, Player},
+    record::GameRecord,
+};
+
+use super::{Contestant, Referee};
+
+/// Width of the confidence interval around the Elo difference: 95% of a normal distribution.
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// The games of a match as seen by its first contestant.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct MatchResult {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub records: Vec<GameRecord>,
+}
+
+impl MatchResult {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Points per game, counting a draw as half a win.
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games().max(1) as f64
+    }
+
+    /// How many Elo points stronger the first contestant played, infinite after a clean
+    /// sweep.
+    pub fn elo_difference(&self) -> f64 {
+        elo(self.score())
+    }
+
+    /// The range the Elo difference lies in with 95% confidence, from the spread of the
+    /// game results around the mean score.
+    pub fn elo_interval(&self) -> (f64, f64) {
+        let games = self.games().max(1) as f64;
+        let score = self.score();
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / games;
+        let margin = CONFIDENCE_Z * (variance / games).sqrt();
+        (
+            elo((score - margin).max(0.0)),
+            elo((score + margin).min(1.0)),
+        )
+    }
+}
+
+/// The Elo difference expected to score `score` points per game.
+fn elo(score: f64) -> f64 {
+    400.0 * (score / (1.0 - score)).log10()
+}
+
+impl Display for MatchResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (low, high) = self.elo_interval();
+        write!(
+            f,
+            "{} wins, {} draws, {} losses, Elo difference {:+.1} ({:+.1}, {:+.1})",
+            self.wins,
+            self.draws,
+            self.losses,
+            self.elo_difference(),
+            low,
+            high
+        )
+    }
+}
+
+/// A number of games on each of a set of starting positions, the contestants swapping
+/// sides after every game.
+pub struct Match {
+    starts: Vec<BoardState>,
+    games: u32,
+}
+
+impl Match {
+    pub fn new(starts: Vec<BoardState>, games: u32) -> Self {
+        Self { starts, games }
+    }
+
+    pub fn play(&self, first: &mut dyn Contestant, second: &mut dyn Contestant) -> MatchResult {
+        let mut result = MatchResult::default();
+        for start in &self.starts {
+            for game in 0..self.games {
+                let first_plays = if game % 2 == 0 { Player::X } else { Player::O };
+                let referee = Referee::new(start.clone());
+                let record = if first_plays == Player::X {
+                    referee.play(first, second)
+                } else {
+                    referee.play(second, first)
+                };
+
+                match record.end.winner() {
+                    Some(winner) if winner == first_plays => result.wins += 1,
+                    Some(_) => result.losses += 1,
+                    None => result.draws += 1,
+                }
+                result.records.push(record);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test_match_runner {
+    use crate::{
+        arena::{Contestant, SolverContestant},
+        parser::{Board, BoardState, MultiMove, Player, TimeSetting},
+        solver::GreedySolver,
+    };
+
+    use super::{Match, MatchResult};
+
+    /// Plays the first playable cell, however bad.
+    struct FirstCell;
+
+    impl Contestant for FirstCell {
+        fn name(&self) -> &str {
+            "first-cell"
+        }
+
+        fn play(&mut self, board_state: &BoardState) -> Result<MultiMove, String> {
+            Ok(MultiMove::from(board_state.get_possible_moves()[0].clone()))
+        }
+    }
+
+    fn start(board: &str) -> BoardState {
+        BoardState::new(
+            Player::X,
+            board.parse::<Board>().unwrap(),
+            TimeSetting::Infinite,
+            None,
+        )
+    }
+
+    fn greedy() -> SolverContestant {
+        SolverContestant::new("greedy", Box::new(GreedySolver::default()))
+    }
+
+    #[test]
+    fn alternates_sides_between_games() {
+        let result = Match::new(vec![start("3_/3_/3_")], 4).play(&mut greedy(), &mut FirstCell);
+        assert_eq!(result.games(), 4);
+        assert_eq!(result.losses, 0);
+    }
+
+    #[test]
+    fn even_results_are_no_elo_difference() {
+        let result = MatchResult {
+            wins: 10,
+            draws: 5,
+            losses: 10,
+            records: vec![],
+        };
+        assert_eq!(result.elo_difference(), 0.0);
+        let (low, high) = result.elo_interval();
+        assert!(low < 0.0 && high > 0.0);
+        assert!((low + high).abs() < 1e-9);
+    }
+
+    #[test]
+    fn three_in_four_points_are_about_191_elo() {
+        let result = MatchResult {
+            wins: 3,
+            draws: 0,
+            losses: 1,
+            records: vec![],
+        };
+        assert!((result.elo_difference() - 190.85).abs() < 0.01);
+    }
+}
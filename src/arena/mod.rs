@@ -0,0 +1,7 @@
+mod contestant;
+mod match_runner;
+mod referee;
+
+pub use contestant::{Contestant, EngineContestant, SolverContestant};
+pub use match_runner::{Match, MatchResult};
+pub use referee::Referee;
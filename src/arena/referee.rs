@@ -0,0 +1,248 @@
+use std::time::Instant;
+
+use crate::{
+    parser::{BoardState, Number, Player, TimeSetting},
+    record::{GameEnd, GameRecord},
+};
+
+use super::Contestant;
+
+/// Runs games between two contestants from a starting position: hands each the position
+/// with their own clock, checks every stone they place against the board's rules and
+/// charges them the time they took.
+///
+/// The time setting of the starting position is the time control. Each side starts with
+/// its own copy of the clock; a `moves-to-go` control adds the starting time again every
+/// time it runs down.
+pub struct Referee {
+    start: BoardState,
+}
+
+impl Referee {
+    pub fn new(start: BoardState) -> Self {
+        Self { start }
+    }
+
+    pub fn play(&self, x: &mut dyn Contestant, o: &mut dyn Contestant) -> GameRecord {
+        let mut state = self.start.clone();
+        let mut clocks = [
+            self.start.time_setting.clone(),
+            self.start.time_setting.clone(),
+        ];
+        let mut turns = Vec::new();
+        let end = loop {
+            if let Some(winner) = state.get_winner() {
+                break GameEnd::Won(winner);
+            }
+            if state.get_possible_moves().is_empty() {
+                break GameEnd::Drawn;
+            }
+
+            let player = state.player_to_move;
+            let clock = &mut clocks[side(player)];
+            state.time_setting = clock.clone();
+            let started = Instant::now();
+            let (turn, name) = match player {
+                Player::X => (x.play(&state), x.name()),
+                Player::O => (o.play(&state), o.name()),
+            };
+            let elapsed = started.elapsed().as_millis().min(u32::MAX as u128) as u32;
+            let turn = match turn {
+                Ok(turn) => turn,
+                Err(error) => break GameEnd::Forfeited(player, error),
+            };
+            if clock.limit().is_some_and(|limit| elapsed > limit.0) {
+                break GameEnd::Forfeited(player, format!("{} ran out of time", name));
+            }
+            *clock = charge(clock, &self.start.time_setting, elapsed);
+
+            match state.apply_turn(&turn) {
+                Ok(next) => state = next,
+                Err(reason) => break GameEnd::Forfeited(player, format!("{} {}", name, reason)),
+            }
+            turns.push(turn);
+        };
+
+        GameRecord {
+            start: self.start.clone(),
+            x: x.name().to_string(),
+            o: o.name().to_string(),
+            turns,
+            end,
+        }
+    }
+}
+
+fn side(player: Player) -> usize {
+    match player {
+        Player::X => 0,
+        Player::O => 1,
+    }
+}
+
+/// The clock after a move that took `elapsed` milliseconds, for a game started on `start`.
+fn charge(clock: &TimeSetting, start: &TimeSetting, elapsed: u32) -> TimeSetting {
+    match clock {
+        TimeSetting::TotalTime(_) | TimeSetting::Infinite => clock.clone(),
+        TimeSetting::TimeRemaining(remaining) => {
+            TimeSetting::TimeRemaining(Number(remaining.0.saturating_sub(elapsed)))
+        }
+        TimeSetting::Clock {
+            remaining,
+            increment,
+            moves_to_go,
+        } => {
+            let mut remaining = remaining
+                .0
+                .saturating_sub(elapsed)
+                .saturating_add(increment.0);
+            let moves_to_go = match (moves_to_go, start) {
+                (Some(moves), _) if moves.0 > 1 => Some(Number(moves.0 - 1)),
+                (
+                    Some(_),
+                    TimeSetting::Clock {
+                        remaining: control,
+                        moves_to_go: Some(moves),
+                        ..
+                    },
+                ) => {
+                    remaining = remaining.saturating_add(control.0);
+                    Some(moves.clone())
+                }
+                (moves_to_go, _) => moves_to_go.clone(),
+            };
+            TimeSetting::Clock {
+                remaining: Number(remaining),
+                increment: increment.clone(),
+                moves_to_go,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_referee {
+    use std::{thread, time::Duration};
+
+    use crate::{
+        parser::{BestMove, Board, BoardState, MultiMove, Number, Player, TimeSetting},
+        record::GameEnd,
+    };
+
+    use super::{charge, Contestant, Referee};
+
+    /// Plays the first playable cell, however bad.
+    struct FirstCell;
+
+    impl Contestant for FirstCell {
+        fn name(&self) -> &str {
+            "first-cell"
+        }
+
+        fn play(&mut self, board_state: &BoardState) -> Result<MultiMove, String> {
+            Ok(MultiMove::from(board_state.get_possible_moves()[0].clone()))
+        }
+    }
+
+    /// Always plays the top left corner, taken or not.
+    struct Stubborn;
+
+    impl Contestant for Stubborn {
+        fn name(&self) -> &str {
+            "stubborn"
+        }
+
+        fn play(&mut self, _: &BoardState) -> Result<MultiMove, String> {
+            Ok(MultiMove::from(BestMove::new(0, 0)))
+        }
+    }
+
+    /// Thinks for a while before playing the first playable cell.
+    struct Slow;
+
+    impl Contestant for Slow {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn play(&mut self, board_state: &BoardState) -> Result<MultiMove, String> {
+            thread::sleep(Duration::from_millis(20));
+            FirstCell.play(board_state)
+        }
+    }
+
+    fn start(board: &str, time_setting: TimeSetting) -> BoardState {
+        BoardState::new(
+            Player::X,
+            board.parse::<Board>().unwrap(),
+            time_setting,
+            None,
+        )
+    }
+
+    #[test]
+    fn records_a_game_to_its_end() {
+        let record = Referee::new(start("3_/3_/3_", TimeSetting::Infinite))
+            .play(&mut FirstCell, &mut FirstCell);
+        assert_eq!(record.end, GameEnd::Won(Player::X));
+        assert_eq!(record.turns.len(), 7);
+        assert_eq!(record.x, "first-cell");
+    }
+
+    #[test]
+    fn forfeits_an_illegal_move() {
+        let record = Referee::new(start("3_/3_/3_", TimeSetting::Infinite))
+            .play(&mut Stubborn, &mut Stubborn);
+        assert!(matches!(record.end, GameEnd::Forfeited(Player::O, _)));
+        assert_eq!(record.turns.len(), 1);
+    }
+
+    #[test]
+    fn forfeits_on_time() {
+        let record = Referee::new(start("3_/3_/3_", TimeSetting::TotalTime(Number(5))))
+            .play(&mut FirstCell, &mut Slow);
+        assert_eq!(
+            record.end,
+            GameEnd::Forfeited(Player::O, "slow ran out of time".to_string())
+        );
+    }
+
+    #[test]
+    fn charges_the_clock_and_adds_the_increment() {
+        let clock = TimeSetting::Clock {
+            remaining: Number(1_000),
+            increment: Number(100),
+            moves_to_go: None,
+        };
+        assert_eq!(
+            charge(&clock, &clock, 300),
+            TimeSetting::Clock {
+                remaining: Number(800),
+                increment: Number(100),
+                moves_to_go: None,
+            }
+        );
+    }
+
+    #[test]
+    fn starts_a_new_time_control_when_the_moves_run_out() {
+        let start = TimeSetting::Clock {
+            remaining: Number(1_000),
+            increment: Number(0),
+            moves_to_go: Some(Number(2)),
+        };
+        let clock = TimeSetting::Clock {
+            remaining: Number(500),
+            increment: Number(0),
+            moves_to_go: Some(Number(1)),
+        };
+        assert_eq!(
+            charge(&clock, &start, 200),
+            TimeSetting::Clock {
+                remaining: Number(1_300),
+                increment: Number(0),
+                moves_to_go: Some(Number(2)),
+            }
+        );
+    }
+}
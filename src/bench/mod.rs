@@ -0,0 +1,5 @@
+mod suite;
+mod test_suite;
+
+pub use suite::{bench, BenchLine, BenchResult, BENCH_DEPTH};
+pub use test_suite::{SuitePosition, SuiteResult, TestSuite};
@@ -0,0 +1,138 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    parser::{BestMove, Board, BoardState, Player, TimeSetting},
+    scorer::GreedyScorer,
+    solver::{LookAheadSolver, Solver},
+};
+
+/// The search depth `bench` uses when none is given.
+pub const BENCH_DEPTH: u32 = 3;
+
+/// The positions searched by `bench`: the board in T3N, the player to move and the win
+/// length. Changing them changes the signature, so add positions rather than edit them.
+const SUITE: [(&str, Player, u32); 8] = [
+    ("3_/3_/3_", Player::X, 3),
+    ("x2_/_o_/3_", Player::X, 3),
+    ("xo_/_x_/2_o", Player::X, 3),
+    ("4_/_xo_/_o2_/x3_", Player::X, 3),
+    ("5_/_x3_/_ox2_/2_o2_/5_", Player::X, 4),
+    ("7_/7_/2_xo3_/2_ox3_/3_x3_/7_/7_", Player::O, 4),
+    ("9_/9_/9_/3_x5_/3_ox4_/4_o4_/9_/9_/9_", Player::X, 5),
+    ("5_/2_x2_/_oxo_/2_x2_/5_", Player::O, 4),
+];
+
+/// The search of one suite position.
+#[derive(Debug)]
+pub struct BenchLine {
+    pub position: BoardState,
+    pub best_move: Option<BestMove>,
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+/// The searches of the whole suite.
+#[derive(Debug)]
+pub struct BenchResult {
+    pub lines: Vec<BenchLine>,
+}
+
+impl BenchResult {
+    pub fn nodes(&self) -> u64 {
+        self.lines.iter().map(|line| line.nodes).sum()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.lines.iter().map(|line| line.elapsed).sum()
+    }
+
+    pub fn nodes_per_second(&self) -> u64 {
+        (self.nodes() as f64 / self.elapsed().as_secs_f64().max(f64::EPSILON)) as u64
+    }
+
+    /// A checksum of the best move and node count of every position, which changes
+    /// whenever a change to the search or the evaluators changes what is searched.
+    pub fn signature(&self) -> u64 {
+        self.lines
+            .iter()
+            .flat_map(|line| {
+                let best_move = line.best_move.as_ref().map(BestMove::to_string);
+                format!("{} {}\n", best_move.unwrap_or_default(), line.nodes).into_bytes()
+            })
+            .fold(FNV_OFFSET, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+}
+
+/// FNV-1a, which unlike the standard library's hasher is the same on every build.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+impl Display for BenchResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            let best_move = match &line.best_move {
+                Some(best_move) => best_move.to_string(),
+                None => "no move".to_string(),
+            };
+            writeln!(
+                f,
+                "{}: {}, {} nodes, {} ms",
+                line.position,
+                best_move,
+                line.nodes,
+                line.elapsed.as_millis()
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Nodes searched: {}", self.nodes())?;
+        writeln!(f, "Time: {} ms", self.elapsed().as_millis())?;
+        writeln!(f, "Nodes per second: {}", self.nodes_per_second())?;
+        write!(f, "Signature: {:016x}", self.signature())
+    }
+}
+
+/// Searches every suite position to `depth` with the engine's solver.
+pub fn bench(depth: u32) -> BenchResult {
+    let lines = SUITE
+        .iter()
+        .map(|(board, player, win_length)| {
+            let board = board.parse::<Board>().expect("suite boards are valid T3N");
+            let position =
+                BoardState::new(*player, board, TimeSetting::Infinite, Some(*win_length));
+            let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), depth);
+            let started = Instant::now();
+            let (best_move, _) = solver.solve(&mut position.clone());
+            BenchLine {
+                position,
+                best_move,
+                nodes: solver.nodes(),
+                elapsed: started.elapsed(),
+            }
+        })
+        .collect();
+    BenchResult { lines }
+}
+
+#[cfg(test)]
+mod test_suite {
+    use super::{bench, SUITE};
+
+    #[test]
+    fn searches_every_position() {
+        let result = bench(1);
+        assert_eq!(result.lines.len(), SUITE.len());
+        assert!(result.lines.iter().all(|line| line.best_move.is_some()));
+        assert_eq!(result.lines[0].nodes, 10);
+    }
+
+    #[test]
+    fn signs_the_same_search_the_same_way() {
+        assert_eq!(bench(1).signature(), bench(1).signature());
+        assert_ne!(bench(1).signature(), bench(2).signature());
+    }
+}
@@ -0,0 +1,234 @@
+use std::{fmt::Display, time::Instant};
+
+use crate::{
+    parser::{BestMove, Board, BoardState, Player, Rules, TimeSetting},
+    solver::Solver,
+};
+
+const BEST: &str = "best";
+const AVOID: &str = "avoid";
+
+/// A position of a test suite with the moves a solver should play there, or should not.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SuitePosition {
+    /// The line of the suite file the position is on.
+    pub line: usize,
+    pub position: BoardState,
+    pub best: Vec<BestMove>,
+    pub avoid: Vec<BestMove>,
+}
+
+impl SuitePosition {
+    /// Whether playing `best_move` solves the position: it is one of the best moves, if
+    /// there are any, and none of the moves to avoid.
+    pub fn is_solved_by(&self, best_move: &BestMove) -> bool {
+        (self.best.is_empty() || self.best.contains(best_move)) && !self.avoid.contains(best_move)
+    }
+}
+
+/// Positions with known answers to check a solver against.
+///
+/// Suite files have one position per line: the board in T3N, the player to move, the win
+/// length and then `best` followed by the moves that solve the position, `avoid` followed
+/// by the moves that do not, or both, e.g. `xx_/oo_/3_ x 3 best c1`. Blank lines and lines
+/// starting with `#` are ignored.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TestSuite {
+    positions: Vec<SuitePosition>,
+}
+
+impl TestSuite {
+    pub fn read(text: &str, rules: Rules) -> Result<Self, String> {
+        let positions = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+            .map(|(number, line)| {
+                position(number + 1, line, &rules)
+                    .map_err(|reason| format!("Suite line {}: {}", number + 1, reason))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { positions })
+    }
+
+    pub fn positions(&self) -> &[SuitePosition] {
+        &self.positions
+    }
+
+    /// Solves every position with `solver`, the position carrying `time_setting`. A move
+    /// that takes longer than the time setting allows fails the position.
+    pub fn run(&self, solver: &dyn Solver, time_setting: &TimeSetting) -> SuiteResult {
+        let mut result = SuiteResult::default();
+        for suite_position in &self.positions {
+            let mut position = suite_position.position.clone();
+            position.time_setting = time_setting.clone();
+            let started = Instant::now();
+            let (best_move, _) = solver.solve(&mut position);
+            let elapsed = started.elapsed().as_millis();
+
+            let failure = match best_move {
+                _ if time_setting
+                    .limit()
+                    .is_some_and(|limit| elapsed > limit.0 as u128) =>
+                {
+                    Some(format!("took {} ms", elapsed))
+                }
+                Some(best_move) if suite_position.is_solved_by(&best_move) => None,
+                Some(best_move) => Some(format!("played {}", best_move)),
+                None => Some("found no move".to_string()),
+            };
+            match failure {
+                Some(reason) => result.failures.push((suite_position.clone(), reason)),
+                None => result.passed += 1,
+            }
+        }
+        result
+    }
+}
+
+fn position(line: usize, text: &str, rules: &Rules) -> Result<SuitePosition, String> {
+    let mut fields = text.split_whitespace();
+    let mut next = |name: &str| fields.next().ok_or_else(|| format!("Missing {}", name));
+    let board = next("board")?
+        .parse::<Board>()
+        .map_err(|error| error.to_string())?;
+    let player = next("player")?.parse::<Player>()?;
+    let win_length = next("win length")?;
+    let win_length = win_length
+        .parse::<u32>()
+        .map_err(|_| format!("{} is not a valid win length.", win_length))?;
+
+    let mut best = Vec::new();
+    let mut avoid = Vec::new();
+    let mut moves = None;
+    for field in fields {
+        match field {
+            BEST => moves = Some(&mut best),
+            AVOID => moves = Some(&mut avoid),
+            _ => moves
+                .as_mut()
+                .ok_or_else(|| format!("Expected {} or {}, found {}", BEST, AVOID, field))?
+                .push(
+                    field
+                        .parse::<BestMove>()
+                        .map_err(|error| error.to_string())?,
+                ),
+        }
+    }
+    if best.is_empty() && avoid.is_empty() {
+        return Err(format!("Missing {} or {} moves", BEST, AVOID));
+    }
+
+    Ok(SuitePosition {
+        line,
+        position: BoardState::new(player, board, TimeSetting::Infinite, Some(win_length))
+            .with_rules(rules.clone()),
+        best,
+        avoid,
+    })
+}
+
+/// How many positions of a suite a solver solved, and why it failed the others.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct SuiteResult {
+    pub passed: usize,
+    pub failures: Vec<(SuitePosition, String)>,
+}
+
+impl SuiteResult {
+    pub fn positions(&self) -> usize {
+        self.passed + self.failures.len()
+    }
+
+    /// The share of the positions solved, from 0 to 1.
+    pub fn pass_rate(&self) -> f64 {
+        self.passed as f64 / self.positions().max(1) as f64
+    }
+}
+
+impl Display for SuiteResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (suite_position, reason) in &self.failures {
+            writeln!(
+                f,
+                "Line {}: {} {}",
+                suite_position.line, suite_position.position, reason
+            )?;
+        }
+        write!(
+            f,
+            "Passed {} of {} ({:.1}%)",
+            self.passed,
+            self.positions(),
+            self.pass_rate() * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_test_suite {
+    use crate::{
+        parser::{BestMove, Rules, TimeSetting},
+        scorer::GreedyScorer,
+        solver::LookAheadSolver,
+    };
+
+    use super::TestSuite;
+
+    const SUITES: [&str; 4] = [
+        include_str!("../../suites/win-in-1.txt"),
+        include_str!("../../suites/block.txt"),
+        include_str!("../../suites/double-threat.txt"),
+        include_str!("../../suites/forced-win.txt"),
+    ];
+
+    #[test]
+    fn reads_best_and_avoid_moves() {
+        let suite = TestSuite::read(
+            "# Corners lose\n\nx2_/_o_/2_x o 3 avoid c1 a3\nxx_/oo_/3_ x 3 best c1",
+            Rules::default(),
+        )
+        .unwrap();
+        let positions = suite.positions();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].line, 3);
+        assert_eq!(
+            positions[0].avoid,
+            vec![BestMove::new(0, 2), BestMove::new(2, 0)]
+        );
+        assert!(positions[0].is_solved_by(&BestMove::new(0, 1)));
+        assert!(!positions[1].is_solved_by(&BestMove::new(0, 1)));
+    }
+
+    #[test]
+    fn rejects_a_position_without_answers() {
+        let error = TestSuite::read("xx_/oo_/3_ x 3", Rules::default()).unwrap_err();
+        assert_eq!(error, "Suite line 1: Missing best or avoid moves");
+    }
+
+    #[test]
+    fn reads_the_shipped_suites() {
+        for suite in SUITES {
+            assert!(!TestSuite::read(suite, Rules::default())
+                .unwrap()
+                .positions()
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn reports_the_positions_a_solver_fails() {
+        let suite = TestSuite::read(
+            "xx_/oo_/3_ x 3 best c1\nxx_/oo_/3_ x 3 best a3",
+            Rules::default(),
+        )
+        .unwrap();
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 1);
+        let result = suite.run(&solver, &TimeSetting::Infinite);
+        assert_eq!(result.passed, 1);
+        assert_eq!(
+            result.to_string(),
+            "Line 2: 2x_/2o_/3_ x win-length 3 played best c1\nPassed 1 of 2 (50.0%)"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::{env::args, process::exit};
+
+use t3elric_engine::{
+    arena::{Contestant, EngineContestant, Match, SolverContestant},
+    cli::{append_records, solver, start_position, take_value, take_values},
+    parser::{Rules, TimeSetting},
+    record::GameEnd,
+};
+
+const USAGE: &str = "Usage: t3elric-match [--games <n>] [--board <rows>x<columns>[:<win-length>]]... \
+[--first <contestant>] [--second <contestant>] [--time <time setting>] [--record <file>] [rule flags]
+
+Contestants are `greedy`, `look-ahead:<depth>` or `engine:<command>`, e.g.
+`engine:./t3elric-engine --gravity`. Time settings are written as in a `move` command,
+e.g. `time-remaining s:60 increment s:1`.";
+
+const DEFAULT_GAMES: u32 = 10;
+const DEFAULT_BOARD: &str = "3x3";
+const DEFAULT_FIRST: &str = "look-ahead:1";
+const DEFAULT_SECOND: &str = "greedy";
+
+fn main() {
+    let result = run(args().skip(1).collect());
+    if let Err(error) = result {
+        eprintln!("{}\n\n{}", error, USAGE);
+        exit(2)
+    }
+}
+
+fn run(mut args: Vec<String>) -> Result<(), String> {
+    let games = match take_value(&mut args, "--games")? {
+        Some(games) => games
+            .parse::<u32>()
+            .map_err(|_| format!("{} is not a valid number of games.", games))?,
+        None => DEFAULT_GAMES,
+    };
+    let mut boards = take_values(&mut args, "--board")?;
+    if boards.is_empty() {
+        boards.push(DEFAULT_BOARD.to_string());
+    }
+    let first = take_value(&mut args, "--first")?.unwrap_or(DEFAULT_FIRST.to_string());
+    let second = take_value(&mut args, "--second")?.unwrap_or(DEFAULT_SECOND.to_string());
+    let time_setting = take_value(&mut args, "--time")?.unwrap_or_default();
+    let time_setting = time_setting.parse::<TimeSetting>()?;
+    let record_path = take_value(&mut args, "--record")?;
+    let rules = Rules::from_args(args)?;
+
+    let starts = boards
+        .iter()
+        .map(|board| {
+            let mut start = start_position(board, &rules)?;
+            start.time_setting = time_setting.clone();
+            Ok(start)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let mut first = contestant(&first)?;
+    let mut second = contestant(&second)?;
+
+    let result = Match::new(starts, games).play(first.as_mut(), second.as_mut());
+    for record in &result.records {
+        if let GameEnd::Forfeited(_, reason) = &record.end {
+            eprintln!("Forfeit: {}", reason);
+        }
+    }
+    println!("{} vs {}: {}", first.name(), second.name(), result);
+
+    if let Some(path) = record_path {
+        append_records(&path, &result.records)?;
+    }
+    Ok(())
+}
+
+fn contestant(spec: &str) -> Result<Box<dyn Contestant>, String> {
+    if let Some(command) = spec.strip_prefix("engine:") {
+        return Ok(Box::new(EngineContestant::from_command(command)?));
+    }
+    Ok(Box::new(SolverContestant::new(spec, solver(spec)?)))
+}
@@ -0,0 +1,51 @@
+use std::{env::args, process::exit};
+
+use t3elric_engine::{
+    arena::{EngineContestant, Referee},
+    cli::{append_records, start_position, take_value},
+    parser::{Rules, TimeSetting},
+    record::GameEnd,
+};
+
+const USAGE: &str = "Usage: t3elric-referee --x <command> --o <command> \
+[--board <rows>x<columns>[:<win-length>]] [--time <time setting>] [--record <file>] [rule flags]
+
+Plays one game between two ST3P engines, each started with its command line, e.g.
+`--x \"./t3elric-engine --gravity\"`, and prints the game record. Time settings are
+written as in a `move` command, e.g. `time-remaining s:60 increment s:1`.";
+
+const DEFAULT_BOARD: &str = "3x3";
+
+fn main() {
+    let result = run(args().skip(1).collect());
+    if let Err(error) = result {
+        eprintln!("{}\n\n{}", error, USAGE);
+        exit(2)
+    }
+}
+
+fn run(mut args: Vec<String>) -> Result<(), String> {
+    let x = take_value(&mut args, "--x")?.ok_or("Missing --x")?;
+    let o = take_value(&mut args, "--o")?.ok_or("Missing --o")?;
+    let board = take_value(&mut args, "--board")?.unwrap_or(DEFAULT_BOARD.to_string());
+    let time_setting = take_value(&mut args, "--time")?.unwrap_or_default();
+    let time_setting = time_setting.parse::<TimeSetting>()?;
+    let record_path = take_value(&mut args, "--record")?;
+    let rules = Rules::from_args(args)?;
+
+    let mut start = start_position(&board, &rules)?;
+    start.time_setting = time_setting;
+    let mut x = EngineContestant::from_command(&x)?;
+    let mut o = EngineContestant::from_command(&o)?;
+
+    let record = Referee::new(start).play(&mut x, &mut o);
+    if let GameEnd::Forfeited(_, reason) = &record.end {
+        eprintln!("Forfeit: {}", reason);
+    }
+    print!("{}", record);
+
+    if let Some(path) = record_path {
+        append_records(&path, &[record])?;
+    }
+    Ok(())
+}
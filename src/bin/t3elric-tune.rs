@@ -0,0 +1,80 @@
+use std::{env::args, fs, process::exit};
+
+use t3elric_engine::{
+    cli::{start_position, take_value, take_values},
+    evaluator::Weights,
+    parser::Rules,
+    tuning::Spsa,
+};
+
+const USAGE: &str = "Usage: t3elric-tune [--iterations <n>] [--games <n>] [--depth <depth>] \
+[--board <rows>x<columns>[:<win-length>]]... [--weights <file>] [--output <file>] [rule flags]
+
+Tunes the evaluator weights by self-play, starting from the weights in `--weights` or the
+defaults, and writes the tuned weights to `--output` or prints them. Start the engine with
+`--weights <file>` to play with them.";
+
+const DEFAULT_ITERATIONS: u32 = 20;
+const DEFAULT_GAMES: u32 = 4;
+const DEFAULT_DEPTH: u32 = 1;
+const DEFAULT_BOARD: &str = "5x5:4";
+
+fn main() {
+    let result = run(args().skip(1).collect());
+    if let Err(error) = result {
+        eprintln!("{}\n\n{}", error, USAGE);
+        exit(2)
+    }
+}
+
+fn run(mut args: Vec<String>) -> Result<(), String> {
+    let iterations = number(take_value(&mut args, "--iterations")?, DEFAULT_ITERATIONS)?;
+    let games = number(take_value(&mut args, "--games")?, DEFAULT_GAMES)?;
+    let depth = number(take_value(&mut args, "--depth")?, DEFAULT_DEPTH)?;
+    let mut boards = take_values(&mut args, "--board")?;
+    if boards.is_empty() {
+        boards.push(DEFAULT_BOARD.to_string());
+    }
+    let weights = match take_value(&mut args, "--weights")? {
+        Some(path) => fs::read_to_string(&path)
+            .map_err(|error| format!("Could not read {}: {}", path, error))?
+            .parse::<Weights>()?,
+        None => Weights::default(),
+    };
+    let output = take_value(&mut args, "--output")?;
+    let rules = Rules::from_args(args)?;
+
+    let starts = boards
+        .iter()
+        .map(|board| start_position(board, &rules))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let weights =
+        Spsa::new(starts, games, depth).tune(weights, iterations, |iteration, weights, result| {
+            let values = weights.values().map(|value| format!("{:.3}", value));
+            eprintln!(
+                "Iteration {}: {} -> {}",
+                iteration,
+                result,
+                values.join(" ")
+            );
+        });
+
+    match output {
+        Some(path) => fs::write(&path, weights.to_string())
+            .map_err(|error| format!("Could not write to {}: {}", path, error)),
+        None => {
+            print!("{}", weights);
+            Ok(())
+        }
+    }
+}
+
+fn number(value: Option<String>, default: u32) -> Result<u32, String> {
+    match value {
+        Some(value) => value
+            .parse::<u32>()
+            .map_err(|_| format!("{} is not a valid number.", value)),
+        None => Ok(default),
+    }
+}
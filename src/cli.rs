@@ -0,0 +1,138 @@
+use std::{fs::OpenOptions, io::Write};
+
+use crate::{
+    parser::{Board, BoardState, Cell, Player, Rules, TimeSetting},
+    record::GameRecord,
+    scorer::GreedyScorer,
+    solver::{GreedySolver, LookAheadSolver, Solver},
+};
+
+/// Removes `flag` and the value after it from `args`, returning the value.
+pub fn take_value(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(format!("Missing value for {}", flag));
+    }
+    args.remove(index);
+    Ok(Some(args.remove(index)))
+}
+
+/// Removes every `flag` and the value after it from `args`, returning the values in order.
+pub fn take_values(args: &mut Vec<String>, flag: &str) -> Result<Vec<String>, String> {
+    let mut values = Vec::new();
+    while let Some(value) = take_value(args, flag)? {
+        values.push(value);
+    }
+    Ok(values)
+}
+
+/// The empty board `<rows>x<columns>[:<win-length>]` describes, X to move under `rules`.
+pub fn start_position(board: &str, rules: &Rules) -> Result<BoardState, String> {
+    let invalid = || format!("{} is not a valid board.", board);
+    let (size, win_length) = match board.split_once(':') {
+        Some((size, win_length)) => (
+            size,
+            Some(win_length.parse::<u32>().map_err(|_| invalid())?),
+        ),
+        None => (board, None),
+    };
+    let (rows, columns) = size.split_once('x').ok_or_else(invalid)?;
+    let rows = rows.parse::<usize>().map_err(|_| invalid())?;
+    let columns = columns.parse::<usize>().map_err(|_| invalid())?;
+
+    let board = Board::new(vec![vec![Cell::Playable; columns]; rows]);
+    Ok(
+        BoardState::new(Player::X, board, TimeSetting::Infinite, win_length)
+            .with_rules(rules.clone()),
+    )
+}
+
+/// The engine's solver `spec` names: `greedy` or `look-ahead:<depth>`.
+pub fn solver(spec: &str) -> Result<Box<dyn Solver>, String> {
+    if spec == "greedy" {
+        return Ok(Box::new(GreedySolver::default()));
+    }
+    if let Some(depth) = spec.strip_prefix("look-ahead:") {
+        let depth = depth
+            .parse::<u32>()
+            .map_err(|_| format!("{} is not a valid search depth.", depth))?;
+        return Ok(Box::new(LookAheadSolver::new(
+            Box::new(GreedyScorer::default()),
+            depth,
+        )));
+    }
+    Err(format!("{} is not a valid solver.", spec))
+}
+
+/// Adds `records` to the end of the file at `path`, each followed by a blank line.
+pub fn append_records(path: &str, records: &[GameRecord]) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|error| format!("Could not open {}: {}", path, error))?;
+    for record in records {
+        writeln!(file, "{}", record)
+            .map_err(|error| format!("Could not write to {}: {}", path, error))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_cli {
+    use crate::parser::{Board, BoardState, Player, Rules, TimeSetting};
+
+    use super::{start_position, take_value, take_values};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn takes_a_flag_and_its_value() {
+        let mut rest = args(&["--gravity", "--book", "book.txt", "--misere"]);
+        assert_eq!(
+            take_value(&mut rest, "--book"),
+            Ok(Some("book.txt".to_string()))
+        );
+        assert_eq!(rest, args(&["--gravity", "--misere"]));
+    }
+
+    #[test]
+    fn takes_every_value_of_a_repeated_flag() {
+        let mut rest = args(&["--board", "3x3", "--gravity", "--board", "7x6:4"]);
+        assert_eq!(
+            take_values(&mut rest, "--board"),
+            Ok(args(&["3x3", "7x6:4"]))
+        );
+        assert_eq!(rest, args(&["--gravity"]));
+    }
+
+    #[test]
+    fn reports_a_missing_value() {
+        let mut rest = args(&["--book"]);
+        assert_eq!(
+            take_value(&mut rest, "--book"),
+            Err("Missing value for --book".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_a_board_size_and_win_length() {
+        assert_eq!(
+            start_position("2x3:2", &Rules::default()),
+            Ok(BoardState::new(
+                Player::X,
+                "3_/3_".parse::<Board>().unwrap(),
+                TimeSetting::Infinite,
+                Some(2)
+            ))
+        );
+        assert_eq!(
+            start_position("3by3", &Rules::default()),
+            Err("3by3 is not a valid board.".to_string())
+        );
+    }
+}
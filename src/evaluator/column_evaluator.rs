@@ -1,48 +1,23 @@
 
-use crate::parser::{BoardState, Cell, Player};
+use crate::parser::{BoardState, Player};
 
-use super::Evaluator;
+use super::{open_line, Evaluator};
 
 pub struct ColumnEvaluator;
 
 impl Evaluator for ColumnEvaluator {
     fn score(&self, board_state: &BoardState, x: usize, y: usize, player: Player) -> f32 {
-        let rows = board_state.board.get_rows();
-        let mut score  = 0;
-        let mut empty_space = 1;
-        for i in (0..x).rev() {
-            if rows[i][y] == Cell::Playable {
-                empty_space += 1;
-                continue;
-            }
+        let (stones, spaces) = open_line(&board_state.board, x, y, (1, 0), player);
+        let mut score = stones;
+        let empty_space = spaces + 1;
 
-            if rows[i][y] == Cell::Played(player) {
-                score += 1
-            } else {
-                break;
-            }
-        }
-
-        for i in x + 1..rows.len() {
-            if rows[i][y] == Cell::Playable {
-                empty_space +=1;
-                continue;
-            }
-
-            if rows[i][y] == Cell::Played(player) {
-                score += 1
-            } else {
-                break;
-            }
-        }
-
-        if score + empty_space < board_state.win_length {
+        if score + empty_space < board_state.win_length
+            || !board_state.can_win_along(x, y, (1, 0), player)
+        {
             score = 0;
         }
 
-        // println!("{}, {}", score, empty_space);
-
-        score as f32 
+        score as f32
     }
 }
 
@@ -53,7 +28,7 @@ mod test_column_evaluator {
         evaluator::Evaluator, parser::{
             Board, BoardState,
             Cell::{Playable, Played},
-            Player, TimeSetting,
+            Player, Rules, TimeSetting, Topology, WinRule,
         }
 
     };
@@ -91,4 +66,49 @@ mod test_column_evaluator {
         let score = ColumnEvaluator.score(&mut board_state, 1, 2, Player::X);
         assert_eq!(score, 1f32);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_score_column_as_0_when_it_makes_an_overline() {
+        let board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Played(Player::X), Playable],
+                vec![Played(Player::X), Playable],
+                vec![Playable, Playable],
+                vec![Played(Player::X), Playable],
+                vec![Playable, Playable],
+            ]),
+            TimeSetting::Infinite,
+            Some(3),
+        )
+        .with_rules(Rules {
+            x_win_rule: WinRule::ExactLength,
+            ..Rules::default()
+        });
+        let score = ColumnEvaluator.score(&board_state, 2, 0, Player::X);
+        assert_eq!(score, 0f32);
+    }
+
+    #[test]
+    fn should_score_column_across_wrapped_edge() {
+        let board = Board::new(vec![
+            vec![Playable],
+            vec![Played(Player::O)],
+            vec![Playable],
+            vec![Played(Player::X)],
+            vec![Played(Player::X)],
+        ]);
+        let flat = BoardState::new(Player::X, board.clone(), TimeSetting::Infinite, Some(3));
+        let wrapped = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3))
+            .with_rules(Rules {
+                topology: Topology {
+                    wrap_horizontal: false,
+                    wrap_vertical: true,
+                },
+                ..Rules::default()
+            });
+
+        assert_eq!(ColumnEvaluator.score(&flat, 0, 0, Player::X), 0f32);
+        assert_eq!(ColumnEvaluator.score(&wrapped, 0, 0, Player::X), 2f32);
+    }
+}
@@ -1,5 +1,5 @@
 
-use crate::parser::{BoardState, Cell, Player};
+use crate::parser::{Board, BoardState, Cell, Player};
 
 use super::Evaluator;
 
@@ -8,66 +8,20 @@ pub struct DiagonalEvaluator;
 impl Evaluator for DiagonalEvaluator {
     fn score(&self, board_state: &BoardState, x: usize, y: usize, player: Player) -> f32 {
         let win_length = board_state.win_length;
-        let rows = board_state.board.get_rows();
-        let row_count = rows.len(); // Number of rows (M)
-        let col_count = rows[0].len(); // Number of columns (M)
-        let mut d1score: i32 = 0;
-        let mut d2score: i32 = 0;
-        let player_cell = Cell::Played(player);
 
-        // Diagonal 1: slope +1 (top-left to bottom-right), y = x + (y - x)
-        let mut row = if x > y { x - y } else { 0 };
-        let mut col = if y > x { y - x } else { 0 };
-
-        let mut d1_empty_cell: i32 = 1;
-        let mut d2_empty_cell: i32 = 1;
-        while row < row_count && col < col_count {
-            if rows[row][col] == player_cell {
-                d1score += 1;
-            }
-
-            if rows[row][col] == Cell::Playable {
-                d1_empty_cell += 1;
-            }
-
-            row += 1;
-            col += 1;
-        }
-
-        if d1_empty_cell + d1score < win_length as i32 {
+        // Diagonal 1: slope +1 (top-left to bottom-right)
+        let (mut d1score, d1_empty) = whole_line(&board_state.board, x, y, (1, 1), player);
+        if d1_empty + 1 + d1score < win_length
+            || !board_state.can_win_along(x, y, (1, 1), player)
+        {
             d1score = 0;
         }
 
-        // Diagonal 2: slope -1 (top-right to bottom-left), y = -x + (x + y)
-        let mut row = if x + y < col_count {
-            0
-        } else {
-            (x + y) - (col_count - 1)
-        };
-        let mut col = if x + y < col_count {
-            x + y
-        } else {
-            col_count - 1
-        };
-
-        while row < row_count && col < col_count {
-            if rows[row][col] == player_cell {
-                d2score += 1;
-            }
-
-            if rows[row][col] == Cell::Playable {
-                d2_empty_cell += 1;
-            }
-
-            if col == 0 {
-                break;
-            }
-
-            row += 1;
-            col -= 1;
-        }
-
-        if d2_empty_cell + d2score < win_length as i32 {
+        // Diagonal 2: slope -1 (top-right to bottom-left)
+        let (mut d2score, d2_empty) = whole_line(&board_state.board, x, y, (-1, 1), player);
+        if d2_empty + 1 + d2score < win_length
+            || !board_state.can_win_along(x, y, (-1, 1), player)
+        {
             d2score = 0;
         }
 
@@ -78,6 +32,27 @@ impl Evaluator for DiagonalEvaluator {
     }
 }
 
+/// Counts the player's stones and the playable cells on the whole line through (x, y)
+/// along `direction`, (x, y) included.
+fn whole_line(board: &Board, x: usize, y: usize, direction: (isize, isize), player: Player) -> (u32, u32) {
+    let (dx, dy) = direction;
+    let ahead = board.walk(x, y, direction).count();
+    let behind = board
+        .cycle_length(direction)
+        .map_or(usize::MAX, |length| length.saturating_sub(1) - ahead);
+
+    board
+        .get_cell(x as isize, y as isize)
+        .into_iter()
+        .chain(board.walk(x, y, direction))
+        .chain(board.walk(x, y, (-dx, -dy)).take(behind))
+        .fold((0, 0), |(stones, spaces), cell| match cell {
+            Cell::Played(stone) if stone == player => (stones + 1, spaces),
+            Cell::Playable => (stones, spaces + 1),
+            _ => (stones, spaces),
+        })
+}
+
 /*
 0 0 0
 0 0 0
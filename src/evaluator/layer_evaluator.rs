@@ -0,0 +1,34 @@
+use crate::parser::{BoardState, Player};
+
+use super::{open_line_along, Evaluator};
+
+/// Scores the lines through a cell that cross from one layer of the board into the
+/// next, which only boards of three or more dimensions have.
+pub struct LayerEvaluator;
+
+impl Evaluator for LayerEvaluator {
+    fn score(&self, board_state: &BoardState, x: usize, y: usize, player: Player) -> f32 {
+        let board = &board_state.board;
+        let layers = board.dimensions() - 2;
+        if layers == 0 {
+            return 0.0;
+        }
+
+        board
+            .directions()
+            .iter()
+            .filter(|direction| direction[..layers].iter().any(|&step| step != 0))
+            .map(|direction| {
+                let (stones, spaces) = open_line_along(board, x, y, direction, player);
+                if stones + spaces + 1 < board_state.win_length
+                    || !board_state.can_win_through(x, y, direction, player)
+                {
+                    0
+                } else {
+                    stones
+                }
+            })
+            .max()
+            .unwrap_or(0) as f32
+    }
+}
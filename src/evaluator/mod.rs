@@ -1,23 +1,81 @@
-use crate::parser::{BoardState, Player};
+use crate::parser::{Board, BoardState, Cell, Player};
 
 pub trait Evaluator {
     fn score(&self, board: &BoardState, x: usize, y: usize, player: Player) -> f32;
 }
 
+/// Counts the player's stones and the playable cells on both sides of (x, y) along
+/// `direction`, each side up to the first cell that blocks the line. A wrapped line is
+/// only counted once round.
+fn open_line(board: &Board, x: usize, y: usize, direction: (isize, isize), player: Player) -> (u32, u32) {
+    let (dx, dy) = direction;
+    count_open_line(
+        [board.walk(x, y, direction), board.walk(x, y, (-dx, -dy))],
+        board.cycle_length(direction),
+        player,
+    )
+}
+
+/// Like [`open_line`] along a direction in all of the board's dimensions.
+fn open_line_along(
+    board: &Board,
+    x: usize,
+    y: usize,
+    direction: &[isize],
+    player: Player,
+) -> (u32, u32) {
+    let point = board.point_of(x, y);
+    let backwards = direction.iter().map(|step| -step).collect();
+    count_open_line(
+        [
+            board.walk_along(point.clone(), direction.to_vec()),
+            board.walk_along(point, backwards),
+        ],
+        board.cycle_length_along(direction),
+        player,
+    )
+}
+
+fn count_open_line(
+    walks: [impl Iterator<Item = Cell>; 2],
+    cycle_length: Option<usize>,
+    player: Player,
+) -> (u32, u32) {
+    let mut remaining = cycle_length.map_or(usize::MAX, |length| length.saturating_sub(1));
+    let (mut stones, mut spaces) = (0, 0);
+    for walk in walks {
+        for cell in walk.take(remaining) {
+            match cell {
+                Cell::Playable => spaces += 1,
+                Cell::Played(stone) if stone == player => stones += 1,
+                _ => break,
+            }
+            remaining -= 1;
+        }
+    }
+    (stones, spaces)
+}
+
 mod column_evaluator;
 mod diagonal_evaluator;
+mod layer_evaluator;
 mod map_evaluator;
 mod opponent_evaluator;
 mod reduce_evaluator;
 mod row_evaluator;
 mod winning_evaluator;
 mod greedy_evaluator;
+mod pipeline;
+mod weights;
 
 pub use column_evaluator::ColumnEvaluator;
 pub use diagonal_evaluator::DiagonalEvaluator;
+pub use layer_evaluator::LayerEvaluator;
 pub use map_evaluator::MapEvaluator;
 pub use opponent_evaluator::OpponentEvaluator;
 pub use reduce_evaluator::ReduceEvaluator;
 pub use row_evaluator::RowEvaluator;
 pub use winning_evaluator::WinningEvaluator;
 pub use greedy_evaluator::GreedyEvaluator;
+pub use pipeline::Pipeline;
+pub use weights::{Weights, WEIGHT_COUNT};
@@ -0,0 +1,144 @@
+use std::fmt::Display;
+
+use super::{
+    ColumnEvaluator, DiagonalEvaluator, Evaluator, LayerEvaluator, MapEvaluator, OpponentEvaluator,
+    ReduceEvaluator, RowEvaluator, Weights, WinningEvaluator,
+};
+
+/// A composition of evaluators, written as an expression and built into an [`Evaluator`]
+/// at runtime:
+///
+/// | Expression | Scores |
+/// | --- | --- |
+/// | `row`, `column`, `diagonal`, `layer` | The player's line through the cell along that direction. |
+/// | `winning(<e>)`, `winning(<e>, <sharpness>)` | How close the line `<e>` scores is to the win length. |
+/// | `opponent(<e>)` | `<e>` for the opponent. |
+/// | `max(<e>, ...)` | The highest of the scores, or 0. |
+/// | `sum(<e>, ...)` | The scores added up. |
+/// | `scale(<e>, <weight>)` | `<e>` times `<weight>`. |
+///
+/// e.g. `max(winning(row), scale(winning(opponent(row)), 1.5))`. Whitespace, newlines and
+/// comments from `#` to the end of the line are ignored.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Pipeline {
+    Row,
+    Column,
+    Diagonal,
+    Layer,
+    Winning(Box<Pipeline>, f32),
+    Opponent(Box<Pipeline>),
+    Max(Vec<Pipeline>),
+    Sum(Vec<Pipeline>),
+    Scale(Box<Pipeline>, f32),
+}
+
+const LINES: [Pipeline; 4] = [
+    Pipeline::Column,
+    Pipeline::Row,
+    Pipeline::Diagonal,
+    Pipeline::Layer,
+];
+
+impl Pipeline {
+    /// The composition the greedy evaluator scores with, under `weights`.
+    pub fn greedy(weights: &Weights) -> Self {
+        let winning = |line: Pipeline| Pipeline::Winning(Box::new(line), weights.sharpness);
+        let scale = |pipeline, weight| Pipeline::Scale(Box::new(pipeline), weight);
+        Pipeline::Max(vec![
+            scale(Pipeline::Max(LINES.map(winning).to_vec()), weights.winning),
+            scale(
+                Pipeline::Max(
+                    LINES
+                        .map(|line| winning(Pipeline::Opponent(Box::new(line))))
+                        .to_vec(),
+                ),
+                weights.losing,
+            ),
+            scale(Pipeline::Max(LINES.to_vec()), weights.player_score),
+            scale(Pipeline::Sum(LINES.to_vec()), weights.player_sum),
+        ])
+    }
+
+    pub fn build(&self) -> Box<dyn Evaluator> {
+        let all = |pipelines: &[Pipeline]| pipelines.iter().map(Pipeline::build).collect();
+        match self {
+            Pipeline::Row => Box::new(RowEvaluator),
+            Pipeline::Column => Box::new(ColumnEvaluator),
+            Pipeline::Diagonal => Box::new(DiagonalEvaluator),
+            Pipeline::Layer => Box::new(LayerEvaluator),
+            Pipeline::Winning(pipeline, sharpness) => Box::new(WinningEvaluator::with_sharpness(
+                pipeline.build(),
+                *sharpness,
+            )),
+            Pipeline::Opponent(pipeline) => Box::new(OpponentEvaluator::new(pipeline.build())),
+            Pipeline::Max(pipelines) => {
+                Box::new(ReduceEvaluator::new(all(pipelines), Box::new(f32::max)))
+            }
+            Pipeline::Sum(pipelines) => {
+                Box::new(ReduceEvaluator::new(all(pipelines), Box::new(|x, y| x + y)))
+            }
+            Pipeline::Scale(pipeline, weight) => {
+                let weight = *weight;
+                Box::new(MapEvaluator::new(
+                    pipeline.build(),
+                    Box::new(move |f| f * weight),
+                ))
+            }
+        }
+    }
+}
+
+impl Display for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |f: &mut std::fmt::Formatter<'_>, name: &str, pipelines: &[Pipeline]| {
+            let pipelines = pipelines
+                .iter()
+                .map(Pipeline::to_string)
+                .collect::<Vec<_>>();
+            write!(f, "{}({})", name, pipelines.join(", "))
+        };
+        match self {
+            Pipeline::Row => write!(f, "row"),
+            Pipeline::Column => write!(f, "column"),
+            Pipeline::Diagonal => write!(f, "diagonal"),
+            Pipeline::Layer => write!(f, "layer"),
+            Pipeline::Winning(pipeline, sharpness) if *sharpness == 1.0 => {
+                write!(f, "winning({})", pipeline)
+            }
+            Pipeline::Winning(pipeline, sharpness) => {
+                write!(f, "winning({}, {})", pipeline, sharpness)
+            }
+            Pipeline::Opponent(pipeline) => write!(f, "opponent({})", pipeline),
+            Pipeline::Max(pipelines) => list(f, "max", pipelines),
+            Pipeline::Sum(pipelines) => list(f, "sum", pipelines),
+            Pipeline::Scale(pipeline, weight) => write!(f, "scale({}, {})", pipeline, weight),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_pipeline {
+    use crate::{
+        evaluator::{ColumnEvaluator, Evaluator, RowEvaluator},
+        parser::{Board, BoardState, Player, TimeSetting},
+    };
+
+    use super::Pipeline;
+
+    #[test]
+    fn builds_the_evaluator_it_describes() {
+        let board_state = BoardState::new(
+            Player::X,
+            "x2_/_x_/3_".parse::<Board>().unwrap(),
+            TimeSetting::Infinite,
+            None,
+        );
+        let evaluator = "sum(row, scale(column, 2))"
+            .parse::<Pipeline>()
+            .unwrap()
+            .build();
+        let expected = RowEvaluator.score(&board_state, 0, 1, Player::X)
+            + 2.0 * ColumnEvaluator.score(&board_state, 0, 1, Player::X);
+        assert_eq!(evaluator.score(&board_state, 0, 1, Player::X), expected);
+    }
+}
@@ -1,45 +1,19 @@
 
-use crate::parser::{BoardState, Cell, Player};
+use crate::parser::{BoardState, Player};
 
-use super::Evaluator;
+use super::{open_line, Evaluator};
 
 pub struct RowEvaluator;
 
 impl Evaluator for RowEvaluator {
     fn score(&self, board_state: &BoardState, x: usize, y: usize, player: Player) -> f32 {
-        let row = board_state.board.get_rows()[x as usize].clone();
+        let (stones, spaces) = open_line(&board_state.board, x, y, (0, 1), player);
+        let mut score = stones as i32;
+        let empty_space = spaces as i32 + 1;
 
-        let mut score: i32= 0;
-        let mut empty_space = 1;
-        for i in (0..y).rev() {
-
-            if row[i] == Cell::Playable {
-                empty_space += 1;
-                continue;
-            }
-
-            if row[i] == Cell::Played(player.clone()) {
-                score += 1
-            } else {
-                break;
-            }
-        }
-
-        for i in y + 1..row.len() {
-
-            if row[i] == Cell::Playable {
-                empty_space += 1;
-                continue;
-            }
-
-            if row[i] == Cell::Played(player.clone()) {
-                score += 1
-            } else {
-                break;
-            }
-        }
-
-        if score + empty_space < board_state.win_length as i32 {
+        if score + empty_space < board_state.win_length as i32
+            || !board_state.can_win_along(x, y, (0, 1), player)
+        {
             score = 0;
         }
 
@@ -0,0 +1,129 @@
+use std::{fmt::Display, str::FromStr};
+
+/// The number of tunable weights.
+pub const WEIGHT_COUNT: usize = 5;
+
+const NAMES: [&str; WEIGHT_COUNT] = [
+    "winning",
+    "losing",
+    "player-score",
+    "player-sum",
+    "sharpness",
+];
+
+/// The numbers the greedy evaluators combine their scores with: how much each kind of
+/// score counts and how sharply a line's score grows as it nears the win length.
+///
+/// Written one weight per line as its name and value, e.g. `losing 1.5`. Weights left out
+/// keep their default.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Weights {
+    /// Lines of the player's own that the move extends towards a win.
+    pub winning: f32,
+    /// Lines of the opponent's that the move blocks.
+    pub losing: f32,
+    /// The longest line of the player's through the move.
+    pub player_score: f32,
+    /// All the lines of the player's through the move together.
+    pub player_sum: f32,
+    /// The power a line's closeness to the win length is raised to.
+    pub sharpness: f32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self::from_values([1.0, 1.5, 1.0, 1.0, 1.0])
+    }
+}
+
+impl Weights {
+    /// The weights in the order they are written.
+    pub fn values(&self) -> [f32; WEIGHT_COUNT] {
+        [
+            self.winning,
+            self.losing,
+            self.player_score,
+            self.player_sum,
+            self.sharpness,
+        ]
+    }
+
+    pub fn from_values(values: [f32; WEIGHT_COUNT]) -> Self {
+        let [winning, losing, player_score, player_sum, sharpness] = values;
+        Self {
+            winning,
+            losing,
+            player_score,
+            player_sum,
+            sharpness,
+        }
+    }
+}
+
+impl FromStr for Weights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut values = Weights::default().values();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || {
+                format!(
+                    "Weights line {}: {} is not a valid weight.",
+                    number + 1,
+                    line
+                )
+            };
+            let (name, value) = line.split_once(' ').ok_or_else(invalid)?;
+            let index = NAMES
+                .iter()
+                .position(|known| *known == name)
+                .ok_or_else(invalid)?;
+            values[index] = value.trim().parse::<f32>().map_err(|_| invalid())?;
+        }
+        Ok(Weights::from_values(values))
+    }
+}
+
+impl Display for Weights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in NAMES.iter().zip(self.values()) {
+            writeln!(f, "{} {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_weights {
+    use super::Weights;
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let weights = Weights::from_values([1.25, 0.5, 2.0, 0.75, 1.5]);
+        assert_eq!(weights.to_string().parse::<Weights>(), Ok(weights));
+    }
+
+    #[test]
+    fn keeps_the_default_of_weights_left_out() {
+        let weights = "# Block harder\nlosing 3".parse::<Weights>().unwrap();
+        assert_eq!(
+            weights,
+            Weights {
+                losing: 3.0,
+                ..Weights::default()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_an_unknown_weight() {
+        assert_eq!(
+            "speed 2".parse::<Weights>(),
+            Err("Weights line 1: speed 2 is not a valid weight.".to_string())
+        );
+    }
+}
@@ -5,11 +5,21 @@ use super::Evaluator;
 
 pub struct WinningEvaluator {
     evaluator: Box<dyn Evaluator>,
+    sharpness: f32,
 }
 
 impl WinningEvaluator {
     pub fn new(evaluator: Box<dyn Evaluator>) -> Self {
-        Self { evaluator }
+        Self::with_sharpness(evaluator, 1.0)
+    }
+
+    /// Raises the score to the power of `sharpness`, so that above 1 lines close to the
+    /// win length count for even more than lines further from it.
+    pub fn with_sharpness(evaluator: Box<dyn Evaluator>, sharpness: f32) -> Self {
+        Self {
+            evaluator,
+            sharpness,
+        }
     }
 }
 
@@ -23,6 +33,6 @@ impl Evaluator for WinningEvaluator {
     ) -> f32 {
         let s = self.evaluator.score(board_state, x, y, player);
         let winlength = board_state.win_length;
-        winlength as f32 / f32::max(winlength as f32 - s, 1.0)
+        (winlength as f32 / f32::max(winlength as f32 - s, 1.0)).powf(self.sharpness)
     }
 }
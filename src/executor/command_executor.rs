@@ -1,27 +1,74 @@
 use either::Either;
 
 use crate::{
-    parser::{Command, CommandResponse, Identity, Quit, Step},
-    solver::Solver,
+    opening::{OpeningBook, SwapChoice, SwapPlayer},
+    parser::{BoardState, Command, CommandResponse, Identity, MultiMove, Quit, Rules, Step},
+    solver::{MoveAnalysis, Solver},
 };
 
 pub struct CommandExecutor {
     solver: Box<dyn Solver>,
+    swap_player: SwapPlayer,
+    book: OpeningBook,
+    rules: Rules,
 }
 
 impl CommandExecutor {
-    pub fn new(solver: Box<dyn Solver>) -> Self {
-        Self { solver }
+    pub fn new(
+        solver: Box<dyn Solver>,
+        swap_player: SwapPlayer,
+        book: OpeningBook,
+        rules: Rules,
+    ) -> Self {
+        Self {
+            solver,
+            swap_player,
+            book,
+            rules,
+        }
     }
 
     pub fn execute(&self, command: Command) -> Either<CommandResponse, Quit> {
         match command {
             Command::Init(version) => Either::Left(CommandResponse::StepOk(Step::new(version))),
             Command::Identify => Either::Left(CommandResponse::Identity(Identity::new())),
-            Command::Move(mut board_state) => Either::Left(CommandResponse::Play(
-                self.solver.solve(&mut board_state).0.unwrap(),
-            )),
+            Command::Move(board_state) => Either::Left(self.play(board_state)),
+            Command::Analyse(top, board_state) => {
+                let mut board_state = board_state.with_rules(self.rules.clone());
+                let mut analyses = self.solver.analyse(&mut board_state);
+                if let Some(top) = top {
+                    analyses.truncate(top as usize);
+                }
+                Either::Left(CommandResponse::Analysis(analyses))
+            }
+            Command::Swap(rule, board_state) => {
+                let board_state = board_state.with_rules(self.rules.clone());
+                Either::Left(match self.swap_player.choose(rule, &board_state) {
+                    SwapChoice::Swap => CommandResponse::Swap,
+                    SwapChoice::PlayOn => self.play(board_state),
+                    SwapChoice::Place(stones) => CommandResponse::Play(stones),
+                    SwapChoice::Side(player) => CommandResponse::Side(player),
+                })
+            }
             Command::Quit => Either::Right(Quit {}),
         }
     }
+
+    /// The principal variations behind the last searched move, once; see
+    /// [`Solver::take_variations`].
+    pub fn take_variations(&self) -> Vec<MoveAnalysis> {
+        self.solver.take_variations()
+    }
+
+    /// Plays a book move where the book knows the position, and searches for one otherwise.
+    /// Turns of several stones are always searched.
+    fn play(&self, board_state: BoardState) -> CommandResponse {
+        let mut board_state = board_state.with_rules(self.rules.clone());
+        if board_state.stones_this_turn() == 1 {
+            if let Some(book_move) = self.book.lookup(&board_state) {
+                return CommandResponse::Play(MultiMove::from(book_move));
+            }
+        }
+        CommandResponse::Play(self.solver.solve_turn(&mut board_state).0.unwrap())
+    }
 }
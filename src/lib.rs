@@ -0,0 +1,11 @@
+pub mod arena;
+pub mod bench;
+pub mod cli;
+pub mod evaluator;
+pub mod executor;
+pub mod opening;
+pub mod parser;
+pub mod record;
+pub mod scorer;
+pub mod solver;
+pub mod tuning;
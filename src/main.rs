@@ -1,37 +1,245 @@
-use std::io::{stdin, Result};
-
-mod evaluator;
-mod executor;
-mod parser;
-mod solver;
-mod scorer;
-use executor::CommandExecutor;
-use parser::Command;
-use scorer::GreedyScorer;
-use solver::{GreedySolver, LookAheadSolver};
-use std::time::Instant; 
-fn main() -> Result<()> {
-    let my_solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 1);
-    // let my_solver = GreedySolver::default();
-    let executor = CommandExecutor::new(Box::new(my_solver));
+use std::{env::args, fs, io::stdin, process::exit};
+
+use t3elric_engine::{
+    bench::{bench, TestSuite, BENCH_DEPTH},
+    cli::{solver, take_value},
+    evaluator::{Pipeline, Weights},
+    executor::CommandExecutor,
+    opening::{OpeningBook, SwapPlayer, BOOK_DEPTH},
+    parser::{Command, Rules, TimeSetting},
+    record::GameRecord,
+    scorer::{GreedyScorer, Network, NetworkScorer, Scorer},
+    solver::{LookAheadSolver, Solver},
+};
+
+const INVALID_INPUT: &str = "Invalid input: ";
+const CLEAR_ADVANTAGE: f32 = 0.05;
+const BOOK: &str = "--book";
+const WEIGHTS: &str = "--weights";
+const EVALUATOR: &str = "--evaluator";
+const NETWORK: &str = "--network";
+const MULTI_PV: &str = "--multi-pv";
+const VARIETY: &str = "--variety";
+const BUILD_BOOK: &str = "build-book";
+const BENCH: &str = "bench";
+const TEST_SUITE: &str = "test-suite";
+const EXPORT_SAMPLES: &str = "export-samples";
+const DEFAULT_SUITE_SOLVER: &str = "look-ahead:1";
+
+fn main() {
+    let result = run(args().skip(1).collect());
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        exit(2)
+    }
+}
+
+fn run(mut args: Vec<String>) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some(BUILD_BOOK) => build_book(args.split_off(1)),
+        Some(BENCH) => run_bench(args.split_off(1)),
+        Some(TEST_SUITE) => run_test_suite(args.split_off(1)),
+        Some(EXPORT_SAMPLES) => export_samples(args.split_off(1)),
+        _ => play(Config::from_args(args)?),
+    }
+}
+
+/// How the engine plays, from the flags it was started with.
+struct Config {
+    rules: Rules,
+    book: OpeningBook,
+    pipeline: Pipeline,
+    network: Option<Network>,
+    multi_pv: usize,
+    variety: f32,
+}
+
+impl Config {
+    fn from_args(mut args: Vec<String>) -> Result<Self, String> {
+        let book_path = take_value(&mut args, BOOK)?;
+        let weights_path = take_value(&mut args, WEIGHTS)?;
+        let evaluator_path = take_value(&mut args, EVALUATOR)?;
+        if weights_path.is_some() && evaluator_path.is_some() {
+            return Err(format!(
+                "{} only weighs the built-in evaluator and cannot be combined with {}.",
+                WEIGHTS, EVALUATOR
+            ));
+        }
+        let network_path = take_value(&mut args, NETWORK)?;
+        let multi_pv = take_value(&mut args, MULTI_PV)?;
+        let variety = take_value(&mut args, VARIETY)?;
+        let rules = Rules::from_args(args)?;
+
+        let book = match book_path {
+            Some(path) => OpeningBook::read(&read(&path)?, rules.clone())?,
+            None => OpeningBook::new(rules.clone()),
+        };
+        let weights = match weights_path {
+            Some(path) => read(&path)?.parse::<Weights>()?,
+            None => Weights::default(),
+        };
+        let pipeline = match evaluator_path {
+            Some(path) => read(&path)?
+                .parse::<Pipeline>()
+                .map_err(|error| error.to_string())?,
+            None => Pipeline::greedy(&weights),
+        };
+        let network = match network_path {
+            Some(path) => Some(read(&path)?.parse::<Network>()?),
+            None => None,
+        };
+        let multi_pv = match multi_pv {
+            Some(count) => count
+                .parse::<usize>()
+                .map_err(|_| format!("{} is not a valid number of principal variations.", count))?,
+            None => 1,
+        };
+        let variety = match variety {
+            Some(margin) => margin
+                .parse::<f32>()
+                .map_err(|_| format!("{} is not a valid score margin.", margin))?,
+            None => 0.0,
+        };
+        Ok(Self {
+            rules,
+            book,
+            pipeline,
+            network,
+            multi_pv,
+            variety,
+        })
+    }
+
+    fn scorer(&self) -> Box<dyn Scorer> {
+        match &self.network {
+            Some(network) => Box::new(NetworkScorer::new(network.clone())),
+            None => Box::new(GreedyScorer::with_evaluator(self.pipeline.build())),
+        }
+    }
+}
+
+/// Answers ST3P commands on stdin until told to quit.
+fn play(config: Config) -> Result<(), String> {
+    let my_solver = LookAheadSolver::new(config.scorer(), 1)
+        .with_multi_pv(config.multi_pv)
+        .with_variety(config.variety);
+    let swap_player = SwapPlayer::new(config.scorer(), CLEAR_ADVANTAGE);
+    let executor =
+        CommandExecutor::new(Box::new(my_solver), swap_player, config.book, config.rules);
     loop {
         let mut buffer = String::new();
-        stdin().read_line(&mut buffer)?;
+        stdin()
+            .read_line(&mut buffer)
+            .map_err(|error| format!("Could not read a command: {}", error))?;
         let input_string = buffer.trim();
-        let start = Instant::now();
         let res = input_string.parse::<Command>();
-        // println!("Parse time: {:?}", start.elapsed());
 
         match res {
             Ok(command) => {
                 executor
                     .execute(command)
                     .map_either(|f| println!("{}", f), |g| g.exit_engine());
+                for variation in executor.take_variations() {
+                    eprintln!("info {}", variation);
+                }
             }
-            Err(msg) => eprintln!("Invalid input: {} || msg: {}", input_string, msg),
+            Err(error) => eprintln!(
+                "{}{}\n{:>width$} {}",
+                INVALID_INPUT,
+                input_string,
+                "^",
+                error,
+                width = INVALID_INPUT.len() + error.offset() + 1
+            ),
         };
+    }
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| format!("Could not read {}: {}", path, error))
+}
 
+fn write(path: &str, contents: String) -> Result<(), String> {
+    fs::write(path, contents).map_err(|error| format!("Could not write to {}: {}", path, error))
+}
+
+/// `build-book <games> <book> [rule flags]`: adds the openings of the game records in
+/// `<games>` to the opening book at `<book>`, creating it if it does not exist yet.
+fn build_book(mut args: Vec<String>) -> Result<(), String> {
+    if args.len() < 2 {
+        return Err(format!("Usage: {} <games> <book> [rule flags]", BUILD_BOOK));
+    }
+    let rest = args.split_off(2);
+    let (games_path, book_path) = (&args[0], &args[1]);
+    let rules = Rules::from_args(rest)?;
+
+    let existing = fs::read_to_string(book_path).unwrap_or_default();
+    let mut book = OpeningBook::read(&existing, rules)?;
+    let records = GameRecord::read_all(&read(games_path)?)?;
+    for record in &records {
+        book.record_game(record, BOOK_DEPTH);
+    }
+    write(book_path, book.to_string())
+}
+
+/// `bench [--depth <depth>]`: searches the built-in position suite and prints the nodes
+/// searched, the time taken and the signature of the search.
+fn run_bench(mut args: Vec<String>) -> Result<(), String> {
+    let depth = match take_value(&mut args, "--depth")? {
+        Some(depth) => depth
+            .parse::<u32>()
+            .map_err(|_| format!("Usage: {} [--depth <depth>]", BENCH))?,
+        None => BENCH_DEPTH,
+    };
+    println!("{}", bench(depth));
+    Ok(())
+}
+
+/// `test-suite <suite> [--solver <solver>] [--time <time setting>] [rule flags]`: checks
+/// a solver against the positions of a test suite file and prints its pass rate, exiting
+/// with 1 if it failed any.
+fn run_test_suite(mut args: Vec<String>) -> Result<(), String> {
+    let usage = format!(
+        "Usage: {} <suite> [--solver <solver>] [--time <time setting>] [rule flags]",
+        TEST_SUITE
+    );
+    if args.is_empty() {
+        return Err(usage);
+    }
+    let suite_path = args.remove(0);
+    let (solver, time_setting, rules) =
+        suite_options(args).map_err(|error| format!("{}\n{}", error, usage))?;
+
+    let suite = TestSuite::read(&read(&suite_path)?, rules)?;
+    let result = suite.run(solver.as_ref(), &time_setting);
+    println!("{}", result);
+    if !result.failures.is_empty() {
+        exit(1)
+    }
+    Ok(())
+}
+
+fn suite_options(mut args: Vec<String>) -> Result<(Box<dyn Solver>, TimeSetting, Rules), String> {
+    let solver_spec = take_value(&mut args, "--solver")?;
+    let solver = solver(solver_spec.as_deref().unwrap_or(DEFAULT_SUITE_SOLVER))?;
+    let time_setting = take_value(&mut args, "--time")?.unwrap_or_default();
+    let time_setting = time_setting.parse::<TimeSetting>()?;
+    Ok((solver, time_setting, Rules::from_args(args)?))
+}
 
-        // println!("Total time: {:?}", start.elapsed());
+/// `export-samples <games> <samples>`: writes every position of the game records in
+/// `<games>` with the result of its game to `<samples>`, as training data for a network.
+fn export_samples(args: Vec<String>) -> Result<(), String> {
+    let [games_path, samples_path] = &args[..] else {
+        return Err(format!("Usage: {} <games> <samples>", EXPORT_SAMPLES));
+    };
+    let records = GameRecord::read_all(&read(games_path)?)?;
+    let mut samples = String::new();
+    for record in &records {
+        for sample in record.samples() {
+            samples.push_str(&sample);
+            samples.push('\n');
+        }
     }
+    write(samples_path, samples)
 }
@@ -0,0 +1,468 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    parser::{BestMove, Board, BoardState, Cell, Player, Rules, TimeSetting},
+    record::GameRecord,
+};
+
+/// Plies of each game [`OpeningBook::record_game`] adds to the book by default.
+pub const BOOK_DEPTH: usize = 8;
+
+/// One of the mirror images and rotations of a flat board, applied as the row flip, then
+/// the column flip and last the swap of rows and columns.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+struct Symmetry {
+    flip_rows: bool,
+    flip_columns: bool,
+    transpose: bool,
+}
+
+impl Symmetry {
+    const IDENTITY: Symmetry = Symmetry {
+        flip_rows: false,
+        flip_columns: false,
+        transpose: false,
+    };
+
+    /// The symmetries of `board` that leave `rules` unchanged. Gravity pulls stones down,
+    /// so only mirroring the columns keeps it, boards of more dimensions are not reduced at
+    /// all and only square boards may swap rows and columns, as long as both or neither
+    /// pair of their edges wrap around.
+    fn of(board: &Board, rules: &Rules) -> Vec<Symmetry> {
+        if board.dimensions() > 2 {
+            return vec![Self::IDENTITY];
+        }
+
+        let square = board.height() == board.column_count();
+        let topology = &rules.topology;
+        let transposable = square && topology.wrap_horizontal == topology.wrap_vertical;
+        let mut symmetries = Vec::new();
+        for flip_rows in [false, true] {
+            for flip_columns in [false, true] {
+                for transpose in [false, true] {
+                    if (flip_rows || transpose) && rules.gravity {
+                        continue;
+                    }
+                    if transpose && !transposable {
+                        continue;
+                    }
+                    symmetries.push(Symmetry {
+                        flip_rows,
+                        flip_columns,
+                        transpose,
+                    });
+                }
+            }
+        }
+        symmetries
+    }
+
+    /// Where the cell at `row`, `column` of a `height` by `width` board ends up.
+    fn apply(&self, row: usize, column: usize, height: usize, width: usize) -> (usize, usize) {
+        let row = if self.flip_rows {
+            height - 1 - row
+        } else {
+            row
+        };
+        let column = if self.flip_columns {
+            width - 1 - column
+        } else {
+            column
+        };
+        if self.transpose {
+            (column, row)
+        } else {
+            (row, column)
+        }
+    }
+
+    /// Where the cell at `row`, `column` of the transformed board came from.
+    fn invert(&self, row: usize, column: usize, height: usize, width: usize) -> (usize, usize) {
+        let (row, column) = if self.transpose {
+            (column, row)
+        } else {
+            (row, column)
+        };
+        Symmetry {
+            transpose: false,
+            ..*self
+        }
+        .apply(row, column, height, width)
+    }
+
+    fn board(&self, board: &Board) -> Board {
+        let rows = board.get_rows();
+        let (height, width) = (rows.len(), board.column_count());
+        let (new_height, new_width) = if self.transpose {
+            (width, height)
+        } else {
+            (height, width)
+        };
+        let mut new_rows = vec![vec![Cell::Playable; new_width]; new_height];
+        for (i, row) in rows.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                let (x, y) = self.apply(i, j, height, width);
+                new_rows[x][y] = *cell;
+            }
+        }
+        Board::new(new_rows)
+    }
+
+    fn best_move(&self, mv: &BestMove, board: &Board) -> BestMove {
+        let (x, y) = self.apply(
+            mv.row() as usize,
+            mv.column() as usize,
+            board.get_rows().len(),
+            board.column_count(),
+        );
+        BestMove::new(x as u32, y as u32)
+    }
+
+    /// Takes a move on the transformed board back to the original `board`.
+    fn original_move(&self, mv: &BestMove, board: &Board) -> BestMove {
+        let (x, y) = self.invert(
+            mv.row() as usize,
+            mv.column() as usize,
+            board.get_rows().len(),
+            board.column_count(),
+        );
+        BestMove::new(x as u32, y as u32)
+    }
+}
+
+/// The position as it is written in the book: the T3N of the smallest of its symmetric
+/// boards under `rules`, the player to move and the win length, together with the
+/// symmetry that leads there.
+fn canonical(board_state: &BoardState, rules: &Rules) -> (String, Symmetry) {
+    let key = |board: &Board| {
+        format!(
+            "{} {} {}",
+            board, board_state.player_to_move, board_state.win_length
+        )
+    };
+    Symmetry::of(&board_state.board, rules)
+        .into_iter()
+        .map(|symmetry| (key(&symmetry.board(&board_state.board)), symmetry))
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .unwrap_or_else(|| (key(&board_state.board), Symmetry::IDENTITY))
+}
+
+/// Weighted moves for known positions, so that the engine does not have to think through
+/// the early game every time. Positions that are mirror images or rotations of each other
+/// share their entries, as far as the rules the book is for allow.
+///
+/// A book file has one entry per line, the position in T3N, the player to move and the
+/// win length followed by a move and its weight, e.g. `3_/_x_/3_ o 3 a1 3`. Positions
+/// only share entries when their win lengths match. Blank lines and lines starting with
+/// `#` are skipped.
+#[derive(PartialEq, Debug, Default)]
+pub struct OpeningBook {
+    rules: Rules,
+    entries: HashMap<String, Vec<(BestMove, u32)>>,
+}
+
+impl OpeningBook {
+    pub fn new(rules: Rules) -> Self {
+        Self {
+            rules,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Reads a book file for games played under `rules`.
+    pub fn read(text: &str, rules: Rules) -> Result<Self, String> {
+        let mut book = OpeningBook::new(rules);
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: String| format!("Book line {}: {}", number + 1, reason);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [board, player, win_length, mv, weight] = fields[..] else {
+                return Err(invalid(
+                    "expected a board, a player, a win length, a move and a weight.".to_string(),
+                ));
+            };
+
+            let board = board
+                .parse::<Board>()
+                .map_err(|error| invalid(error.to_string()))?;
+            let player = player.parse::<Player>().map_err(invalid)?;
+            let win_length = win_length
+                .parse::<u32>()
+                .map_err(|_| invalid(format!("{} is not a valid win length.", win_length)))?;
+            let mv = mv
+                .parse::<BestMove>()
+                .map_err(|error| invalid(error.to_string()))?;
+            let weight = weight
+                .parse::<u32>()
+                .map_err(|_| invalid(format!("{} is not a valid weight.", weight)))?;
+
+            let board_state =
+                BoardState::new(player, board, TimeSetting::Infinite, Some(win_length));
+            book.add(&board_state, &mv, weight);
+        }
+        Ok(book)
+    }
+
+    /// Adds `weight` to playing `mv` in `board_state`.
+    pub fn add(&mut self, board_state: &BoardState, mv: &BestMove, weight: u32) {
+        let (key, symmetry) = canonical(board_state, &self.rules);
+        let mv = symmetry.best_move(mv, &board_state.board);
+        let moves = self.entries.entry(key).or_default();
+        match moves.iter_mut().find(|(known, _)| *known == mv) {
+            Some((_, known_weight)) => *known_weight += weight,
+            None => moves.push((mv, weight)),
+        }
+    }
+
+    /// Picks one of the legal book moves for `board_state` at random, each as likely as its
+    /// weight, or `None` for positions the book does not know.
+    pub fn lookup(&self, board_state: &BoardState) -> Option<BestMove> {
+        let moves = self.legal_moves(board_state);
+        let total: u64 = moves.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+        pick(&moves, fastrand::u64(..total))
+    }
+
+    /// The book moves for `board_state` that its rules allow there, with their weights.
+    fn legal_moves(&self, board_state: &BoardState) -> Vec<(BestMove, u64)> {
+        let (key, symmetry) = canonical(board_state, &self.rules);
+        let possible = board_state.get_possible_moves();
+        self.entries
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .map(|(mv, weight)| {
+                let mv = symmetry.original_move(mv, &board_state.board);
+                (mv, *weight as u64)
+            })
+            .filter(|(mv, _)| possible.contains(mv))
+            .collect()
+    }
+
+    /// Adds the first `depth` stones of a recorded game, weighing each by how the game
+    /// turned out for the player who placed it: 2 for a win, 1 for a draw and nothing for
+    /// a loss.
+    pub fn record_game(&mut self, record: &GameRecord, depth: usize) {
+        let winner = record.end.winner();
+        let mut state = record.start.clone();
+        for mv in record
+            .turns
+            .iter()
+            .flat_map(|turn| turn.moves())
+            .take(depth)
+        {
+            let weight = match winner {
+                Some(player) if player == state.player_to_move => 2,
+                Some(_) => 0,
+                None => 1,
+            };
+            if weight > 0 {
+                self.add(&state, mv, weight);
+            }
+            state = state.apply_move(mv);
+        }
+    }
+}
+
+/// Writes the book in its file format, sorted by position so that rebuilt books diff well.
+/// The move that `draw`, below the total weight of `moves`, falls on.
+fn pick(moves: &[(BestMove, u64)], mut draw: u64) -> Option<BestMove> {
+    for (mv, weight) in moves {
+        if draw < *weight {
+            return Some(mv.clone());
+        }
+        draw -= weight;
+    }
+    None
+}
+
+impl Display for OpeningBook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut positions: Vec<_> = self.entries.iter().collect();
+        positions.sort_by_key(|(position, _)| *position);
+        for (position, moves) in positions {
+            for (mv, weight) in moves {
+                writeln!(
+                    f,
+                    "{} {}{} {}",
+                    position,
+                    mv.get_column_notation(),
+                    mv.get_row_notation(),
+                    weight
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_opening_book {
+    use crate::{
+        parser::{BestMove, Board, BoardState, Player, Rules, TimeSetting, Topology},
+        record::GameRecord,
+    };
+
+    use super::{pick, OpeningBook};
+
+    fn read(text: &str) -> Result<OpeningBook, String> {
+        OpeningBook::read(text, Rules::default())
+    }
+
+    fn board_state(board: &str, player: Player) -> BoardState {
+        BoardState::new(
+            player,
+            board.parse::<Board>().unwrap(),
+            TimeSetting::Infinite,
+            None,
+        )
+    }
+
+    #[test]
+    fn finds_moves_of_mirrored_and_rotated_positions() {
+        let book = read("x2_/3_/3_ o 3 b2 1").unwrap();
+        assert_eq!(
+            book.lookup(&board_state("3_/3_/2_x", Player::O)),
+            Some(BestMove::new(1, 1))
+        );
+
+        let book = read("x2_/3_/3_ o 3 b1 1").unwrap();
+        assert_eq!(
+            book.lookup(&board_state("2_x/3_/3_", Player::O)),
+            Some(BestMove::new(0, 1))
+        );
+        assert_eq!(
+            book.lookup(&board_state("3_/3_/x2_", Player::O)),
+            Some(BestMove::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn only_mirrors_columns_under_gravity() {
+        let rules = Rules {
+            gravity: true,
+            ..Rules::default()
+        };
+        let book = OpeningBook::read("3_/3_/x2_ o 3 c3 1", rules.clone()).unwrap();
+        assert_eq!(
+            book.lookup(&board_state("3_/3_/2_x", Player::O).with_rules(rules.clone())),
+            Some(BestMove::new(2, 0))
+        );
+        assert_eq!(
+            book.lookup(&board_state("x2_/3_/3_", Player::O).with_rules(rules)),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_positions_of_other_win_lengths_apart() {
+        let book = read("4_/4_/4_/4_ x 4 b2 1").unwrap();
+        let state = |win_length| {
+            BoardState::new(
+                Player::X,
+                "4_/4_/4_/4_".parse::<Board>().unwrap(),
+                TimeSetting::Infinite,
+                Some(win_length),
+            )
+        };
+        assert_eq!(book.lookup(&state(4)), Some(BestMove::new(1, 1)));
+        assert_eq!(book.lookup(&state(3)), None);
+    }
+
+    #[test]
+    fn only_swaps_rows_and_columns_when_both_or_neither_edges_wrap() {
+        let wrap = |wrap_horizontal, wrap_vertical| Rules {
+            topology: Topology {
+                wrap_horizontal,
+                wrap_vertical,
+            },
+            ..Rules::default()
+        };
+        let transposed = board_state("3_/x2_/3_", Player::O);
+        for (rules, expected) in [
+            (wrap(true, false), None),
+            (wrap(false, true), None),
+            (wrap(true, true), Some(BestMove::new(0, 0))),
+        ] {
+            let book = OpeningBook::read("_x_/3_/3_ o 3 a1 1", rules.clone()).unwrap();
+            assert_eq!(book.lookup(&transposed.clone().with_rules(rules)), expected);
+        }
+    }
+
+    #[test]
+    fn picks_moves_by_weight() {
+        let book = read("3_/3_/3_ x 3 b2 3\n3_/3_/3_ x 3 a1 1").unwrap();
+        let moves = book.legal_moves(&board_state("3_/3_/3_", Player::X));
+        let picks: Vec<_> = (0..4).map(|draw| pick(&moves, draw)).collect();
+        assert_eq!(
+            picks,
+            vec![
+                Some(BestMove::new(1, 1)),
+                Some(BestMove::new(1, 1)),
+                Some(BestMove::new(1, 1)),
+                Some(BestMove::new(0, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_picks_moves_the_rules_allow() {
+        let rules = Rules {
+            gravity: true,
+            ..Rules::default()
+        };
+        let book =
+            OpeningBook::read("3_/3_/3_ x 3 a1 1000\n3_/3_/3_ x 3 a3 1", rules.clone()).unwrap();
+        let state = board_state("3_/3_/3_", Player::X).with_rules(rules);
+        for _ in 0..10 {
+            assert_eq!(book.lookup(&state), Some(BestMove::new(2, 0)));
+        }
+    }
+
+    #[test]
+    fn records_the_winners_moves() {
+        let mut book = OpeningBook::default();
+        let records =
+            GameRecord::read_all("[Board \"3_/3_/3_\"]\n[Result \"x\"]\n\nb2 a1 c1 a3 a2\n")
+                .unwrap();
+        book.record_game(&records[0], 3);
+        assert_eq!(book.to_string(), "2_o/_x_/3_ x 3 a1 2\n3_/3_/3_ x 3 b2 2\n");
+    }
+
+    #[test]
+    fn weighs_draws_for_both_players() {
+        let mut book = OpeningBook::default();
+        let records = GameRecord::read_all(
+            "[Board \"3_/3_/3_\"]\n[Result \"draw\"]\n\nb2 a1\n\n\
+             [Board \"3_/3_/3_\"]\n[Result \"x\"]\n\nb2 b1\n",
+        )
+        .unwrap();
+        for record in &records {
+            book.record_game(record, 2);
+        }
+        assert_eq!(book.to_string(), "3_/3_/3_ x 3 b2 3\n3_/_x_/3_ o 3 a1 1\n");
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let book = read("# 3x3\n\n3_/_x_/3_ o 3 a1 2\n3_/_x_/3_ o 3 b1 1\n").unwrap();
+        assert_eq!(read(&book.to_string()), Ok(book));
+    }
+
+    #[test]
+    fn reports_the_line_of_a_bad_entry() {
+        let book = read("3_/3_/3_ x 3 b2 1\n3_/3_/3_ x b2");
+        assert_eq!(
+            book,
+            Err(
+                "Book line 2: expected a board, a player, a win length, a move and a weight."
+                    .to_string()
+            )
+        );
+    }
+}
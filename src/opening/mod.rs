@@ -0,0 +1,5 @@
+mod book;
+mod swap_player;
+
+pub use book::{OpeningBook, BOOK_DEPTH};
+pub use swap_player::{SwapChoice, SwapPlayer};
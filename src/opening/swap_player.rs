@@ -0,0 +1,213 @@
+use crate::{
+    parser::{BoardState, MultiMove, Player, SwapRule},
+    scorer::Scorer,
+};
+
+/// Stones the first player places to open a swap2 game: X, O and X.
+const SWAP2_OPENING_STONES: usize = 3;
+/// Stones the second player may add in swap2 instead of picking a side: O and X.
+const SWAP2_EXTRA_STONES: usize = 2;
+
+/// What a player does with the turn a swap protocol gives them.
+#[derive(PartialEq, Debug)]
+pub enum SwapChoice {
+    /// Takes over the side that placed the opening.
+    Swap,
+    /// Keeps the side to move and plays an ordinary move.
+    PlayOn,
+    /// Places these stones, leaving the choice of side to the opponent.
+    Place(MultiMove),
+    /// Picks the side to play once the opponent has placed the last opening stones.
+    Side(Player),
+}
+
+/// Plays the swap2 and pie opening protocols by weighing the positions each choice leads
+/// to with the scorer, see [`Scorer::weigh`].
+pub struct SwapPlayer {
+    scorer: Box<dyn Scorer>,
+    /// The share of the board's line potential a side has to be ahead by before the scorer
+    /// is trusted to pick it; closer swap2 openings are handed back with two more stones.
+    clear_advantage: f32,
+}
+
+impl SwapPlayer {
+    pub fn new(scorer: Box<dyn Scorer>, clear_advantage: f32) -> Self {
+        Self {
+            scorer,
+            clear_advantage,
+        }
+    }
+
+    /// Answers the opening of `rule` for the player to move in `board_state`, who plays on
+    /// as usual once the opening is over.
+    pub fn choose(&self, rule: SwapRule, board_state: &BoardState) -> SwapChoice {
+        let stones = board_state.board.stone_count();
+        match rule {
+            SwapRule::Pie if stones > 0 => self.swap_or_play_on(board_state),
+            SwapRule::Swap2 if stones == 0 => {
+                SwapChoice::Place(self.balanced_stones(board_state, SWAP2_OPENING_STONES))
+            }
+            SwapRule::Swap2 if stones == SWAP2_OPENING_STONES => {
+                let (advantage, potential) = self.scorer.weigh(board_state);
+                if advantage.abs() < self.clear_advantage * potential {
+                    SwapChoice::Place(self.balanced_stones(board_state, SWAP2_EXTRA_STONES))
+                } else {
+                    self.swap_or_play_on(board_state)
+                }
+            }
+            SwapRule::Swap2 if stones == SWAP2_OPENING_STONES + SWAP2_EXTRA_STONES => {
+                if self.scorer.weigh(board_state).0 >= 0f32 {
+                    SwapChoice::Side(Player::X)
+                } else {
+                    SwapChoice::Side(Player::O)
+                }
+            }
+            _ => SwapChoice::PlayOn,
+        }
+    }
+
+    /// Swapping hands over the same position with the sides exchanged, so it pays exactly
+    /// when the other side is ahead.
+    fn swap_or_play_on(&self, board_state: &BoardState) -> SwapChoice {
+        let (advantage, _) = self.scorer.weigh(board_state);
+        if advantage * f32::from(board_state.player_to_move as i16) < 0f32 {
+            SwapChoice::Swap
+        } else {
+            SwapChoice::PlayOn
+        }
+    }
+
+    /// Places `count` stones for alternating sides, each where it leaves neither side
+    /// ahead, so that the opponent gains as little as possible from picking one.
+    fn balanced_stones(&self, board_state: &BoardState, count: usize) -> MultiMove {
+        let mut state = board_state.clone();
+        let mut stones = Vec::new();
+        for _ in 0..count {
+            if state.is_game_over() {
+                break;
+            }
+            let best = state
+                .get_possible_moves()
+                .into_iter()
+                .map(|m| (self.scorer.weigh(&state.apply_move(&m)).0.abs(), m))
+                .min_by(|(a, _), (b, _)| a.total_cmp(b));
+            let Some((_, best_move)) = best else {
+                break;
+            };
+            state = state.apply_move(&best_move);
+            stones.push(best_move);
+        }
+        MultiMove::new(stones)
+    }
+}
+
+#[cfg(test)]
+mod test_swap_player {
+    use crate::{
+        parser::{Board, BoardState, Player, SwapRule, TimeSetting},
+        scorer::{GreedyScorer, Network, NetworkScorer},
+    };
+
+    use super::{SwapChoice, SwapPlayer};
+
+    /// Rates a position by how many more stones X has than O.
+    const STONE_DIFFERENCE: &str = "network
+convolution 5 2
+0 0 0 0 1 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 1 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0
+0 0
+dense 2 1
+10 -10
+0
+";
+
+    fn swap_player() -> SwapPlayer {
+        SwapPlayer::new(Box::new(GreedyScorer::default()), 0.05)
+    }
+
+    fn network_swap_player() -> SwapPlayer {
+        let network = STONE_DIFFERENCE.parse::<Network>().unwrap();
+        SwapPlayer::new(Box::new(NetworkScorer::new(network)), 0.05)
+    }
+
+    fn board_state(board: &str, player: Player) -> BoardState {
+        BoardState::new(
+            player,
+            board.parse::<Board>().unwrap(),
+            TimeSetting::Infinite,
+            Some(3),
+        )
+    }
+
+    #[test]
+    fn swaps_an_opening_that_leaves_the_other_side_ahead() {
+        let choice = swap_player().choose(SwapRule::Pie, &board_state("xx_/3_/o2_", Player::O));
+        assert_eq!(choice, SwapChoice::Swap);
+    }
+
+    #[test]
+    fn plays_on_after_an_opening_that_leaves_it_ahead() {
+        let choice = swap_player().choose(SwapRule::Pie, &board_state("oo_/3_/x2_", Player::O));
+        assert_eq!(choice, SwapChoice::PlayOn);
+    }
+
+    #[test]
+    fn plays_on_when_there_is_no_opening_to_swap() {
+        let choice = swap_player().choose(SwapRule::Pie, &board_state("4_/4_/4_/4_", Player::X));
+        assert_eq!(choice, SwapChoice::PlayOn);
+    }
+
+    #[test]
+    fn places_three_stones_to_open_swap2() {
+        let choice = swap_player().choose(SwapRule::Swap2, &board_state("4_/4_/4_/4_", Player::X));
+        let SwapChoice::Place(stones) = choice else {
+            panic!("expected stones, got {:?}", choice);
+        };
+        assert_eq!(stones.moves().len(), 3);
+    }
+
+    #[test]
+    fn hands_back_a_close_swap2_opening_with_two_more_stones() {
+        let choice =
+            swap_player().choose(SwapRule::Swap2, &board_state("x2_o/4_/4_/_x2_", Player::O));
+        let SwapChoice::Place(stones) = choice else {
+            panic!("expected stones, got {:?}", choice);
+        };
+        assert_eq!(stones.moves().len(), 2);
+    }
+
+    #[test]
+    fn picks_the_side_that_is_ahead_after_five_stones() {
+        let choice = swap_player().choose(
+            SwapRule::Swap2,
+            &board_state("xx2_/o3_/_o2_/x3_", Player::O),
+        );
+        assert_eq!(choice, SwapChoice::Side(Player::X));
+    }
+
+    #[test]
+    fn plays_on_once_the_opening_is_over() {
+        let choice =
+            swap_player().choose(SwapRule::Swap2, &board_state("xo2_/4_/4_/4_", Player::X));
+        assert_eq!(choice, SwapChoice::PlayOn);
+    }
+
+    #[test]
+    fn weighs_positions_the_same_way_with_a_network() {
+        let player = network_swap_player();
+        assert_eq!(
+            player.choose(SwapRule::Pie, &board_state("xx_/3_/o2_", Player::O)),
+            SwapChoice::Swap
+        );
+        assert_eq!(
+            player.choose(SwapRule::Pie, &board_state("oo_/3_/x2_", Player::O)),
+            SwapChoice::PlayOn
+        );
+        assert_eq!(
+            player.choose(
+                SwapRule::Swap2,
+                &board_state("xx2_/o3_/_o2_/x3_", Player::O)
+            ),
+            SwapChoice::Side(Player::X)
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::generate_token_parser;
+
+use super::{
+    and_parser::{And2, And3},
+    move_parser::{BoardState, MoveOptions, MoveOptionsParser, T3NParser},
+    number_parser::Number,
+    whitespace_parser::WhiteSpaceParser,
+    Board, ParseResult, Parser, Player, TimeSetting,
+};
+
+pub struct AnalyseTokenParser;
+
+pub const ANALYSE: &str = "analyse";
+
+generate_token_parser!(ANALYSE, AnalyseTokenParser);
+
+pub struct TopTokenParser;
+
+pub const TOP: &str = "top";
+
+generate_token_parser!(TOP, TopTokenParser);
+
+/// Parses the optional `top <n> ` that limits an analysis to the `n` best moves.
+pub struct TopParser;
+
+impl Parser<Option<Number>> for TopParser {
+    fn parse_from(val: &str) -> ParseResult<'_, Option<Number>> {
+        match And3::<TopTokenParser, WhiteSpaceParser, And2<Number, WhiteSpaceParser>>::parse_from(
+            val,
+        ) {
+            Ok(((_, (_, (top, _))), rest)) => Ok((Some(top), rest)),
+            Err(_) => Ok((None, val)),
+        }
+    }
+}
+
+/// Parses `analyse [top <n>] <t3n> <player> [options]`, which takes the same arguments as
+/// `move`.
+pub type AnalyseParser =
+    And2<And3<AnalyseTokenParser, WhiteSpaceParser, And2<TopParser, T3NParser>>, MoveOptionsParser>;
+pub type AnalyseParserReturnType = (
+    (
+        &'static str,
+        (&'static str, (Option<Number>, (Board, Player))),
+    ),
+    MoveOptions,
+);
+
+pub fn from_analyse_parser(value: AnalyseParserReturnType) -> (Option<u32>, BoardState) {
+    let ((_, (_, (top, (board, player)))), options) = value;
+    let board_state = BoardState::new(
+        player,
+        board,
+        options.time_setting.unwrap_or(TimeSetting::Infinite),
+        options.win_length,
+    );
+    (top.map(|top| top.0), board_state)
+}
+
+#[cfg(test)]
+mod test_analyse_parser {
+    use crate::parser::{
+        board::Board,
+        move_parser::{BoardState, Player},
+        time_parser::TimeSetting,
+        Parser,
+    };
+
+    use super::{from_analyse_parser, AnalyseParser};
+
+    #[test]
+    fn parses_every_move() {
+        let res = AnalyseParser::parse_from("analyse 3_/_x_/3_ o win-length 3")
+            .map(|(value, _)| from_analyse_parser(value));
+        assert_eq!(
+            res,
+            Ok((
+                None,
+                BoardState::new(
+                    Player::O,
+                    "3_/_x_/3_".parse::<Board>().unwrap(),
+                    TimeSetting::Infinite,
+                    Some(3)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_the_top_moves() {
+        let res = AnalyseParser::parse_from("analyse top 5 15_/15_/15_ x")
+            .map(|(value, _)| from_analyse_parser(value));
+        assert_eq!(
+            res.map(|(top, board_state)| (top, board_state.player_to_move)),
+            Ok((Some(5), Player::X))
+        );
+    }
+}
@@ -7,9 +7,11 @@ where
     P1: Parser<X>,
     P2: Parser<Y>,
 {
-    fn parse_from(val: &String) -> ParseResult<(X, Y)> {
+    fn parse_from(val: &str) -> ParseResult<'_, (X, Y)> {
         P1::parse_from(val).and_then(|(x, follow)| {
-            P2::parse_from(&follow).map(|(y, remaining)| ((x, y), remaining))
+            P2::parse_from(follow)
+                .map(|(y, remaining)| ((x, y), remaining))
+                .map_err(|error| error.shift(val.len() - follow.len()))
         })
     }
 }
@@ -0,0 +1,655 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{Cell, Player, WinRule};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Board {
+    pub(super) rows: Vec<Vec<Cell>>,
+    topology: Topology,
+    /// Sizes of the dimensions above rows and columns, outermost first: empty for a flat
+    /// board and `[4]` for a 4x4x4 cube. The layers are stacked one after another in `rows`.
+    pub(super) layers: Vec<usize>,
+}
+
+/// Which edges of a board join up, so that lines running off one side carry on from the
+/// opposite side.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Topology {
+    /// The left and right edges meet.
+    pub wrap_horizontal: bool,
+    /// The top and bottom edges meet.
+    pub wrap_vertical: bool,
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (wrap_horizontal, wrap_vertical) = match s {
+            "none" => (false, false),
+            "horizontal" => (true, false),
+            "vertical" => (false, true),
+            "both" => (true, true),
+            _ => return Err(format!("{} is not a valid wrap.", s)),
+        };
+        Ok(Self {
+            wrap_horizontal,
+            wrap_vertical,
+        })
+    }
+}
+
+impl Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.wrap_horizontal, self.wrap_vertical) {
+            (false, false) => write!(f, "none"),
+            (true, false) => write!(f, "horizontal"),
+            (false, true) => write!(f, "vertical"),
+            (true, true) => write!(f, "both"),
+        }
+    }
+}
+
+impl Board {
+    pub fn new(rows: Vec<Vec<Cell>>) -> Self {
+        Self {
+            rows,
+            topology: Topology::default(),
+            layers: Vec::new(),
+        }
+    }
+
+    /// Stacks equally shaped boards as the layers of a board with one more dimension.
+    pub fn stack(layers: Vec<Board>) -> Self {
+        let mut shape = vec![layers.len()];
+        shape.extend(
+            layers
+                .first()
+                .map_or(Vec::new(), |layer| layer.layers.clone()),
+        );
+        Self {
+            rows: layers.into_iter().flat_map(|layer| layer.rows).collect(),
+            topology: Topology::default(),
+            layers: shape,
+        }
+    }
+
+    /// Number of dimensions: 2 for a flat board, 3 for a cube and so on.
+    pub fn dimensions(&self) -> usize {
+        self.layers.len() + 2
+    }
+
+    /// Number of rows in each layer.
+    pub fn height(&self) -> usize {
+        self.rows.len() / self.layers.iter().product::<usize>().max(1)
+    }
+
+    /// Splits row `x` of the stacked rows into the row its layer starts at and the row
+    /// within that layer.
+    fn split_row(&self, x: usize) -> (usize, isize) {
+        if self.layers.is_empty() {
+            return (0, x as isize);
+        }
+        let height = self.height().max(1);
+        (x - x % height, (x % height) as isize)
+    }
+
+    /// Joins up the board's edges. A stacked board's edges join up within each layer.
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn get_rows(&self) -> Vec<Vec<Cell>> {
+        self.rows.clone()
+    }
+
+    pub fn stone_count(&self) -> usize {
+        self.rows
+            .iter()
+            .flatten()
+            .filter(|cell| matches!(cell, Cell::Played(_)))
+            .count()
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.len())
+    }
+
+    /// The rows a stone dropped into `column` comes to rest in, one for each layer: the
+    /// layer's lowest playable cell.
+    pub fn drop_rows(&self, column: usize) -> impl Iterator<Item = usize> + '_ {
+        let height = self.height().max(1);
+        (0..self.rows.len())
+            .step_by(height)
+            .filter_map(move |base| {
+                (base..base + height)
+                    .rev()
+                    .find(|&row| self.rows[row].get(column) == Some(&Cell::Playable))
+            })
+    }
+
+    pub fn play_move(&mut self, x: u32, y: u32, player: Player) {
+        if let Some(row) = self.rows.get_mut(x as usize) {
+            if let Some(cell) = row.get_mut(y as usize) {
+                *cell = Cell::Played(player);
+            }
+        }
+    }
+
+    /// The cell at (x, y) of the stacked rows. Only a flat board's rows wrap here, as a
+    /// stacked board's rows wrap within their layer.
+    pub fn get_cell(&self, x: isize, y: isize) -> Option<Cell> {
+        let x = if self.topology.wrap_vertical && self.layers.is_empty() && !self.rows.is_empty() {
+            x.rem_euclid(self.rows.len() as isize)
+        } else {
+            x
+        };
+        let y = if self.topology.wrap_horizontal && self.column_count() > 0 {
+            y.rem_euclid(self.column_count() as isize)
+        } else {
+            y
+        };
+
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.rows.get(x as usize)?.get(y as usize).copied()
+    }
+
+    /// Where (x, y) of the layer starting at row `base` lies in the stacked rows, wrapping
+    /// rows and columns as the topology says; `None` off the layer.
+    fn layer_position(&self, base: usize, x: isize, y: isize) -> Option<(usize, usize)> {
+        let height = self.height();
+        let columns = self.column_count();
+        let x = if self.topology.wrap_vertical && height > 0 {
+            x.rem_euclid(height as isize)
+        } else {
+            x
+        };
+        let y = if self.topology.wrap_horizontal && columns > 0 {
+            y.rem_euclid(columns as isize)
+        } else {
+            y
+        };
+
+        if x < 0 || y < 0 || x as usize >= height || y as usize >= columns {
+            return None;
+        }
+        Some((base + x as usize, y as usize))
+    }
+
+    /// The cell at (x, y) of the layer starting at row `base`, which is row 0 on a flat board.
+    fn layer_cell(&self, base: usize, x: isize, y: isize) -> Option<Cell> {
+        if self.layers.is_empty() {
+            return self.get_cell(x, y);
+        }
+        let (x, y) = self.layer_position(base, x, y)?;
+        Some(self.rows[x][y])
+    }
+
+    /// Where `steps` cells along `direction` from (x, y) lands, staying in the layer of (x, y).
+    pub(super) fn position_along(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+        steps: isize,
+    ) -> Option<(usize, usize)> {
+        let (dx, dy) = direction;
+        let (base, x) = self.split_row(x);
+        self.layer_position(base, x + steps * dx, y as isize + steps * dy)
+    }
+
+    pub(super) fn cell_along(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+        steps: isize,
+    ) -> Option<Cell> {
+        let (dx, dy) = direction;
+        let (base, x) = self.split_row(x);
+        self.layer_cell(base, x + steps * dx, y as isize + steps * dy)
+    }
+
+    /// The coordinates of (x, y) of the stacked rows in all of the board's dimensions,
+    /// outermost layer first and column last.
+    pub fn point_of(&self, x: usize, y: usize) -> Vec<isize> {
+        let height = self.height().max(1);
+        let mut layer = x / height;
+        let mut point = vec![0; self.layers.len()];
+        for (coordinate, size) in point.iter_mut().zip(&self.layers).rev() {
+            *coordinate = (layer % size) as isize;
+            layer /= size;
+        }
+        point.extend([(x % height) as isize, y as isize]);
+        point
+    }
+
+    /// The cell `steps` cells along `direction` from `point`, in all of the board's dimensions.
+    pub fn cell_at(&self, point: &[isize], direction: &[isize], steps: isize) -> Option<Cell> {
+        let coordinate = |i: usize| point[i] + steps * direction[i];
+        let mut layer = 0;
+        for (i, &size) in self.layers.iter().enumerate() {
+            let coordinate = coordinate(i);
+            if coordinate < 0 || coordinate as usize >= size {
+                return None;
+            }
+            layer = layer * size + coordinate as usize;
+        }
+
+        let plane = self.layers.len();
+        self.layer_cell(
+            layer * self.height(),
+            coordinate(plane),
+            coordinate(plane + 1),
+        )
+    }
+
+    /// The part of `direction` within a layer, if it does not cross layers.
+    pub fn in_layer(&self, direction: &[isize]) -> Option<(isize, isize)> {
+        let (layers, plane) = direction.split_at(self.layers.len());
+        if layers.iter().any(|&step| step != 0) {
+            return None;
+        }
+        Some((plane[0], plane[1]))
+    }
+
+    /// Every direction a line can run in, one of each opposite pair: the 4 of a flat board,
+    /// the 13 of a cube, and (3^n - 1) / 2 in n dimensions.
+    pub fn directions(&self) -> Vec<Vec<isize>> {
+        let mut directions: Vec<Vec<isize>> = vec![Vec::new()];
+        for _ in 0..self.dimensions() {
+            directions = directions
+                .into_iter()
+                .flat_map(|direction| {
+                    [-1, 0, 1].map(|step| {
+                        let mut direction = direction.clone();
+                        direction.push(step);
+                        direction
+                    })
+                })
+                .collect();
+        }
+        directions.retain(|direction| direction.iter().find(|&&step| step != 0) == Some(&1));
+        directions
+    }
+
+    /// How many cells the line along `direction` has when it wraps back onto itself,
+    /// `None` when it runs off the board instead.
+    pub fn cycle_length(&self, direction: (isize, isize)) -> Option<usize> {
+        let (dx, dy) = direction;
+        let rows = self.height();
+        let columns = self.column_count();
+        match (dx != 0, dy != 0) {
+            (true, false) if self.topology.wrap_vertical => Some(rows),
+            (false, true) if self.topology.wrap_horizontal => Some(columns),
+            (true, true) if self.topology.wrap_vertical && self.topology.wrap_horizontal => {
+                Some(rows / gcd(rows, columns) * columns)
+            }
+            _ => None,
+        }
+    }
+
+    /// Like [`Board::cycle_length`] for a direction in all dimensions. Only rows and
+    /// columns wrap, so lines that cross layers never do.
+    pub fn cycle_length_along(&self, direction: &[isize]) -> Option<usize> {
+        self.in_layer(direction)
+            .and_then(|direction| self.cycle_length(direction))
+    }
+
+    /// The cells met walking away from (x, y) along `direction` within its layer. The walk
+    /// ends at the edge of the board or, on a wrapped line, just before it comes back round
+    /// to (x, y).
+    pub fn walk(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = Cell> + '_ {
+        let (dx, dy) = direction;
+        let limit = self
+            .cycle_length(direction)
+            .map_or(usize::MAX, |length| length.saturating_sub(1));
+        let (base, x) = self.split_row(x);
+        (1isize..)
+            .map_while(move |i| self.layer_cell(base, x + i * dx, y as isize + i * dy))
+            .take(limit)
+    }
+
+    /// Like [`Board::walk`] from a point along a direction in all dimensions.
+    pub fn walk_along(
+        &self,
+        point: Vec<isize>,
+        direction: Vec<isize>,
+    ) -> impl Iterator<Item = Cell> + '_ {
+        let limit = self
+            .cycle_length_along(&direction)
+            .map_or(usize::MAX, |length| length.saturating_sub(1));
+        (1isize..)
+            .map_while(move |i| self.cell_at(&point, &direction, i))
+            .take(limit)
+    }
+
+    /// Measures the run of `player` stones through (x, y) along `direction`, treating
+    /// (x, y) itself as one of the player's stones.
+    pub fn run_through(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+        player: Player,
+    ) -> Run {
+        let (dx, dy) = direction;
+        measure_run(
+            self.walk(x, y, direction),
+            self.walk(x, y, (-dx, -dy)),
+            self.cycle_length(direction).is_some(),
+            player,
+        )
+    }
+
+    /// Like [`Board::run_through`] from a point along a direction in all dimensions.
+    pub fn run_along(&self, point: &[isize], direction: &[isize], player: Player) -> Run {
+        let backwards = direction.iter().map(|step| -step).collect();
+        measure_run(
+            self.walk_along(point.to_vec(), direction.to_vec()),
+            self.walk_along(point.to_vec(), backwards),
+            self.cycle_length_along(direction).is_some(),
+            player,
+        )
+    }
+
+    pub fn check_win(&self, player: Player, win_length: u32, rule: WinRule) -> bool {
+        let stone = Cell::Played(player);
+        // Lines within a layer run along the flat board's directions, so only the
+        // ones that cross layers are left over.
+        let crossing: Vec<Vec<isize>> = match self.layers.is_empty() {
+            true => Vec::new(),
+            false => self
+                .directions()
+                .into_iter()
+                .filter(|direction| self.in_layer(direction).is_none())
+                .collect(),
+        };
+        for (x, row) in self.rows.iter().enumerate() {
+            for (y, cell) in row.iter().enumerate() {
+                if *cell != stone {
+                    continue;
+                }
+
+                for plane in DIRECTIONS {
+                    // Every run is measured once, from its first stone. A run that wraps all
+                    // the way round has no first stone, so wrapped lines are measured from each.
+                    if self.cycle_length(plane).is_none()
+                        && self.cell_along(x, y, plane, -1) == Some(stone)
+                    {
+                        continue;
+                    }
+                    if rule.is_win(&self.run_through(x, y, plane, player), win_length, player) {
+                        return true;
+                    }
+                }
+
+                if crossing.is_empty() {
+                    continue;
+                }
+                let point = self.point_of(x, y);
+                for direction in &crossing {
+                    if self.cell_at(&point, direction, -1) == Some(stone) {
+                        continue;
+                    }
+                    let run = self.run_along(&point, direction, player);
+                    if rule.is_win(&run, win_length, player) {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Measures a run through a stone from the cells walking away from it either way; the
+/// walks are exhausted without meeting an end only when the run wraps all the way round.
+fn measure_run(
+    forward: impl Iterator<Item = Cell>,
+    backward: impl Iterator<Item = Cell>,
+    wraps: bool,
+    player: Player,
+) -> Run {
+    let (ahead, after) = stones_along(forward, player);
+    if after.is_none() && wraps {
+        // The whole wrapped line is the player's, it has no ends.
+        return Run {
+            length: ahead + 1,
+            before: None,
+            after: None,
+        };
+    }
+    let (behind, before) = stones_along(backward, player);
+
+    Run {
+        length: ahead + behind + 1,
+        before,
+        after,
+    }
+}
+
+/// Counts the player's stones along a walk, and the cell after them.
+fn stones_along(mut walk: impl Iterator<Item = Cell>, player: Player) -> (u32, Option<Cell>) {
+    let mut stones = 0;
+    loop {
+        match walk.next() {
+            Some(cell) if cell == Cell::Played(player) => stones += 1,
+            end => return (stones, end),
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The directions a line can run in: along a row, down a column and the two diagonals.
+pub const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (-1, 1)];
+
+/// A maximal run of one player's stones along a line, with the cells just past each end
+/// (`None` past the edge of the board).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Run {
+    pub length: u32,
+    pub before: Option<Cell>,
+    pub after: Option<Cell>,
+}
+
+#[cfg(test)]
+mod test_topology {
+    use std::str::FromStr;
+
+    use crate::parser::{Board, Player, WinRule};
+
+    use super::{Run, Topology};
+
+    const HORIZONTAL: Topology = Topology {
+        wrap_horizontal: true,
+        wrap_vertical: false,
+    };
+    const VERTICAL: Topology = Topology {
+        wrap_horizontal: false,
+        wrap_vertical: true,
+    };
+    const BOTH: Topology = Topology {
+        wrap_horizontal: true,
+        wrap_vertical: true,
+    };
+
+    #[test]
+    fn rows_wrap_horizontally() {
+        let board = Board::from_str("xx_xx/5_/5_/5_/5_").unwrap();
+
+        assert!(!board.check_win(Player::X, 4, WinRule::FreeStyle));
+        assert!(board.clone().with_topology(HORIZONTAL).check_win(
+            Player::X,
+            4,
+            WinRule::FreeStyle
+        ));
+        assert!(!board
+            .with_topology(VERTICAL)
+            .check_win(Player::X, 4, WinRule::FreeStyle));
+    }
+
+    #[test]
+    fn columns_wrap_vertically() {
+        let board = Board::from_str("x4_/5_/5_/x4_/x4_").unwrap();
+
+        assert!(!board.check_win(Player::X, 3, WinRule::FreeStyle));
+        assert!(board
+            .with_topology(VERTICAL)
+            .check_win(Player::X, 3, WinRule::FreeStyle));
+    }
+
+    #[test]
+    fn diagonals_wrap_through_corners_only_when_both_edges_do() {
+        let board = Board::from_str("x3_/_xx_/3_x").unwrap();
+
+        for topology in [HORIZONTAL, VERTICAL] {
+            assert!(!board.clone().with_topology(topology).check_win(
+                Player::X,
+                4,
+                WinRule::FreeStyle
+            ));
+        }
+        assert!(board
+            .with_topology(BOTH)
+            .check_win(Player::X, 4, WinRule::FreeStyle));
+    }
+
+    #[test]
+    fn a_line_that_wraps_all_the_way_round_has_no_ends() {
+        let board = Board::from_str("x_x/3_/3_")
+            .unwrap()
+            .with_topology(HORIZONTAL);
+
+        assert_eq!(
+            board.run_through(0, 1, (0, 1), Player::X),
+            Run {
+                length: 3,
+                before: None,
+                after: None,
+            }
+        );
+        assert!(!Board::from_str("3x/3_/3_")
+            .unwrap()
+            .with_topology(HORIZONTAL)
+            .check_win(Player::X, 4, WinRule::FreeStyle));
+    }
+
+    #[test]
+    fn diagonals_on_a_torus_cycle_through_every_row_and_column() {
+        let board = Board::from_str("3_/3_").unwrap().with_topology(BOTH);
+
+        assert_eq!(board.cycle_length((1, 1)), Some(6));
+        assert_eq!(board.walk(0, 0, (1, 1)).count(), 5);
+        assert_eq!(board.cycle_length((1, 0)), Some(2));
+        assert_eq!(Board::from_str("3_/3_").unwrap().cycle_length((1, 0)), None);
+    }
+
+    #[test]
+    fn parse_topology() {
+        assert_eq!("both".parse(), Ok(BOTH));
+        assert_eq!("none".parse(), Ok(Topology::default()));
+    }
+
+    #[test]
+    fn rows_wrap_within_their_layer() {
+        let board = Board::from_str("4_/4_/4_/4_|x3_/4_/x3_/x3_").unwrap();
+        assert!(!board.check_win(Player::X, 3, WinRule::FreeStyle));
+        assert!(board
+            .with_topology(VERTICAL)
+            .check_win(Player::X, 3, WinRule::FreeStyle));
+
+        let board = Board::from_str("x3_/4_/4_/4_|4_/4_/x3_/x3_")
+            .unwrap()
+            .with_topology(VERTICAL);
+        assert!(!board.check_win(Player::X, 3, WinRule::FreeStyle));
+        assert_eq!(board.walk(7, 0, (1, 0)).count(), 3);
+        assert_eq!(board.get_cell(8, 0), None);
+    }
+}
+
+#[cfg(test)]
+mod test_layers {
+    use std::str::FromStr;
+
+    use crate::parser::{Board, ParseError, Player, WinRule};
+
+    /// A 4x4x4 board from its layers, top layer first.
+    fn cube(layers: [&str; 4]) -> Board {
+        Board::from_str(&layers.join("|")).unwrap()
+    }
+
+    #[test]
+    fn layered_boards_round_trip() {
+        for board in ["4_/4_|4_/4_", "x_/_o|2_/2_||2_/2_|o_/_x"] {
+            assert_eq!(Board::from_str(board).unwrap().to_string(), board);
+        }
+        assert_eq!(Board::from_str("4_/4_|4_/4_").unwrap().dimensions(), 3);
+        assert_eq!(Board::from_str("_|_||_|_").unwrap().dimensions(), 4);
+    }
+
+    #[test]
+    fn layers_must_be_the_same_size() {
+        assert_eq!(
+            Board::from_str("4_/4_|4_"),
+            Err(ParseError::invalid("layers differ in size", "4_").shift(6))
+        );
+    }
+
+    #[test]
+    fn every_dimension_adds_directions() {
+        assert_eq!(Board::from_str("3_/3_/3_").unwrap().directions().len(), 4);
+        assert_eq!(Board::from_str("_|_").unwrap().directions().len(), 13);
+        assert_eq!(Board::from_str("_|_||_|_").unwrap().directions().len(), 40);
+    }
+
+    #[test]
+    fn lines_run_straight_down_through_the_layers() {
+        let board = cube(["x3_/4_/4_/4_"; 4]);
+        assert!(board.check_win(Player::X, 4, WinRule::FreeStyle));
+    }
+
+    #[test]
+    fn lines_run_along_the_space_diagonals() {
+        let board = cube([
+            "x3_/4_/4_/4_",
+            "4_/_x2_/4_/4_",
+            "4_/4_/2_x_/4_",
+            "4_/4_/4_/3_x",
+        ]);
+        assert!(board.check_win(Player::X, 4, WinRule::FreeStyle));
+    }
+
+    #[test]
+    fn lines_stop_at_the_edge_of_their_layer() {
+        let board = cube([
+            "4_/4_/x3_/x3_",
+            "x3_/x3_/4_/4_",
+            "4_/4_/4_/4_",
+            "4_/4_/4_/4_",
+        ]);
+        assert!(!board.check_win(Player::X, 4, WinRule::FreeStyle));
+        assert_eq!(board.walk(3, 0, (1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn stones_drop_within_their_layer() {
+        let board = Board::from_str("3_/x2_|3_/3_").unwrap();
+        assert_eq!(board.drop_rows(0).collect::<Vec<_>>(), vec![0, 3]);
+    }
+}
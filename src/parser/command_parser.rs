@@ -4,31 +4,49 @@ use either::Either;
 use std::str::FromStr;
 
 use super::{
+    analyse_parser::{from_analyse_parser, AnalyseParser, AnalyseParserReturnType, ANALYSE},
     identify_parser::{IdentifyParser, Identity},
     move_parser::{BoardState, MoveParser, MoveParserReturnType},
     number_parser::Number,
-    or_parser::Or4,
+    or_parser::Or6,
     quit_parser::QuitParser,
     step_parser::{Step, StepParser, StepParserReturnType},
-    BestMove, Parser,
+    swap_parser::{from_swap_parser, SwapParser, SwapParserReturnType, SwapRule, SWAP},
+    MultiMove, ParseError, Parser, Player,
 };
+use crate::solver::MoveAnalysis;
 
 #[derive(Eq, PartialEq, Debug)]
 pub enum Command {
     Init(Number),
     Identify,
     Move(BoardState),
+    /// Scores the moves of a position, all of them or only as many of the best as given.
+    Analyse(Option<u32>, BoardState),
+    /// The opening of a swap protocol, to be answered by swapping sides, placing stones
+    /// or choosing a side.
+    Swap(SwapRule, BoardState),
     Quit,
 }
 
-pub type CommandParser = Or4<StepParser, IdentifyParser, MoveParser, QuitParser>;
-pub type ComandParserReturnType = (
-    Either<StepParserReturnType, Either<String, Either<MoveParserReturnType, String>>>,
-    String,
+pub type CommandParser =
+    Or6<StepParser, IdentifyParser, MoveParser, AnalyseParser, SwapParser, QuitParser>;
+pub type ComandParserReturnType<'a> = (
+    Either<
+        StepParserReturnType,
+        Either<
+            &'static str,
+            Either<
+                MoveParserReturnType,
+                Either<AnalyseParserReturnType, Either<SwapParserReturnType, &'static str>>,
+            >,
+        >,
+    >,
+    &'a str,
 );
 
-impl From<ComandParserReturnType> for Command {
-    fn from(value: ComandParserReturnType) -> Self {
+impl<'a> From<ComandParserReturnType<'a>> for Command {
+    fn from(value: ComandParserReturnType<'a>) -> Self {
         match value.0 {
             Either::Left(output) => {
                 let (_, (_, (_, (_, version)))) = output;
@@ -39,22 +57,38 @@ impl From<ComandParserReturnType> for Command {
             Either::Right(Either::Right(Either::Left(output))) => {
                 Command::Move(BoardState::from(output))
             }
-            Either::Right(Either::Right(Either::Right(_))) => Command::Quit,
+            Either::Right(Either::Right(Either::Right(Either::Left(output)))) => {
+                let (top, board_state) = from_analyse_parser(output);
+                Command::Analyse(top, board_state)
+            }
+            Either::Right(Either::Right(Either::Right(Either::Right(Either::Left(output))))) => {
+                let (rule, board_state) = from_swap_parser(output);
+                Command::Swap(rule, board_state)
+            }
+            Either::Right(Either::Right(Either::Right(Either::Right(Either::Right(_))))) => {
+                Command::Quit
+            }
         }
     }
 }
 
 impl FromStr for Command {
-    type Err = String;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        CommandParser::parse_from(&s.to_string()).map(Command::from)
+        CommandParser::parse_from(s).map(Command::from)
     }
 }
 
 pub enum CommandResponse {
     StepOk(Step),
     Identity(Identity),
-    Play(BestMove),
+    Play(MultiMove),
+    /// Candidate moves, best first, with their scores and expected lines of play.
+    Analysis(Vec<MoveAnalysis>),
+    /// Takes over the other side in a swap protocol.
+    Swap,
+    /// Picks the side to play once the opponent has placed the last opening stones.
+    Side(Player),
 }
 
 impl Display for CommandResponse {
@@ -62,7 +96,15 @@ impl Display for CommandResponse {
         match self {
             CommandResponse::StepOk(step) => write!(f, "{} ok", step),
             CommandResponse::Identity(identity) => write!(f, "{}identify ok", identity),
-            CommandResponse::Play(best_move) => write!(f, "{}", best_move),
+            CommandResponse::Play(multi_move) => write!(f, "{}", multi_move),
+            CommandResponse::Analysis(analyses) => {
+                for analysis in analyses {
+                    writeln!(f, "{} {}", ANALYSE, analysis)?;
+                }
+                write!(f, "{} ok", ANALYSE)
+            }
+            CommandResponse::Swap => write!(f, "{}", SWAP),
+            CommandResponse::Side(player) => write!(f, "side {}", player),
         }
     }
 }
@@ -70,23 +112,24 @@ impl Display for CommandResponse {
 #[cfg(test)]
 mod test_command {
     use crate::parser::{
-        command_parser::Command,
+        board::Board,
+        command_parser::{Command, CommandResponse},
         move_parser::{
-            Board, BoardState,
+            BestMove, BoardState,
             Cell::{Playable, Played},
             Player,
         },
         time_parser::TimeSetting,
-        Parser,
+        ParseError, Parser,
     };
+    use crate::solver::MoveAnalysis;
 
     use super::CommandParser;
 
     #[test]
     fn test_move() {
-        let move_string = String::from("move 3_/_x_/3_ o win-length 3");
-        let val = &move_string.clone();
-        let command = CommandParser::parse_from(val).map(Command::from);
+        let move_string = "move 3_/_x_/3_ o win-length 3";
+        let command = CommandParser::parse_from(move_string).map(Command::from);
         assert_eq!(
             command,
             Ok(Command::Move(BoardState::new(
@@ -101,4 +144,51 @@ mod test_command {
             )))
         );
     }
+
+    #[test]
+    fn test_analyse() {
+        let command = "analyse top 2 3_/_x_/3_ o".parse::<Command>();
+        assert!(matches!(command, Ok(Command::Analyse(Some(2), _))));
+    }
+
+    #[test]
+    fn displays_one_line_per_analysed_move() {
+        let response = CommandResponse::Analysis(vec![
+            MoveAnalysis {
+                best_move: BestMove::new(1, 1),
+                score: 12.5,
+                variation: vec![BestMove::new(1, 1), BestMove::new(0, 0)],
+            },
+            MoveAnalysis {
+                best_move: BestMove::new(0, 0),
+                score: -3.0,
+                variation: vec![BestMove::new(0, 0)],
+            },
+        ]);
+        assert_eq!(
+            response.to_string(),
+            "analyse b2 score 12.5 pv b2 a1\nanalyse a1 score -3 pv a1\nanalyse ok"
+        );
+    }
+
+    #[test]
+    fn reports_all_commands_for_unknown_input() {
+        let command = "mvoe 3_/3_/3_ x".parse::<Command>();
+        assert_eq!(
+            command,
+            Err(ParseError::expected_one_of(
+                &["st3p", "identify", "move", "analyse", "swap2", "swap", "quit"],
+                "mvoe"
+            ))
+        );
+    }
+
+    #[test]
+    fn reports_position_of_invalid_cell() {
+        let command = "move 3_/_y_/3_ o".parse::<Command>();
+        assert_eq!(
+            command,
+            Err(ParseError::expected_one_of(&["_", ".", "x", "o"], "y").shift(9))
+        );
+    }
 }
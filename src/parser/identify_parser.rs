@@ -2,8 +2,6 @@ use std::{collections::HashMap, fmt::Display};
 
 use crate::generate_token_parser;
 
-use super::{ParseResult, Parser};
-
 #[derive(PartialEq, Eq, Debug)]
 pub struct Identity {
     engine_info: HashMap<String, String>,
@@ -42,21 +40,24 @@ impl Display for Identity {
 #[cfg(test)]
 mod test_identify_token_parser {
 
-    use crate::parser::{identify_parser::IDENTIFY, Parser};
+    use crate::parser::{identify_parser::IDENTIFY, ParseError, Parser};
 
     use super::IdentifyParser;
 
     #[test]
     fn parse_version_token() {
-        let identify_string = "identify".to_string();
-        let res = IdentifyParser::parse_from(&identify_string);
-        assert_eq!(Ok((IDENTIFY.to_string(), "".to_string())), res);
+        let identify_string = "identify";
+        let res = IdentifyParser::parse_from(identify_string);
+        assert_eq!(Ok((IDENTIFY, "")), res);
     }
 
     #[test]
     fn error_invalid_token() {
-        let invalid_identify_string = "identifx".to_string();
-        let res = IdentifyParser::parse_from(&invalid_identify_string);
-        assert_eq!(Err(String::from("Could not find token: identify")), res);
+        let invalid_identify_string = "identifx";
+        let res = IdentifyParser::parse_from(invalid_identify_string);
+        assert_eq!(
+            Err(ParseError::expected(IDENTIFY, invalid_identify_string)),
+            res
+        );
     }
 }
@@ -1,5 +1,6 @@
+mod analyse_parser;
 mod and_parser;
-mod anything_parser;
+mod board;
 mod command_parser;
 mod digit_parser;
 mod identify_parser;
@@ -7,27 +8,37 @@ mod move_parser;
 mod nothing_parser;
 mod number_parser;
 mod or_parser;
+mod parse_error;
 mod parse_result;
 mod parser;
+mod pipeline_parser;
 mod quit_parser;
+mod renju;
+mod rules;
 mod step_parser;
+mod swap_parser;
 mod time_parser;
 mod token_parser;
 mod version_parser;
 mod whitespace_parser;
 
+pub use board::{Board, Topology};
 pub use command_parser::{Command, CommandParser, CommandResponse};
 pub use digit_parser::Digit;
 pub use identify_parser::Identity;
 pub use move_parser::BestMove;
-pub use move_parser::Board;
 pub use move_parser::BoardState;
 pub use move_parser::Cell;
+pub use move_parser::MultiMove;
 pub use move_parser::Player;
+pub use number_parser::Number;
+pub use parse_error::ParseError;
 pub use parse_result::ParseResult;
 pub use parser::Parser;
 pub use quit_parser::Quit;
+pub use rules::{Rules, StonesPerTurn, WinRule};
 pub use step_parser::Step;
 pub use step_parser::StepParser;
+pub use swap_parser::SwapRule;
 pub use time_parser::TimeSetting;
 pub use version_parser::Version;
@@ -6,16 +6,17 @@ use crate::generate_token_parser;
 
 use super::{
     and_parser::{And2, And3},
-    anything_parser::{Anything, AnythingParser},
-    nothing_parser::{Nothing, NothingParser},
+    board::{Board, Run},
+    nothing_parser::NothingParser,
     number_parser::Number,
-    or_parser::{Or2, Or3},
-    time_parser::{TimeRemainingParser, TimeSetting, TotalTimeParser},
+    or_parser::Or5,
+    time_parser::{
+        IncrementParser, MovesToGoParser, TimeRemainingParser, TimeSetting, TotalTimeParser,
+    },
     whitespace_parser::WhiteSpaceParser,
-    ParseResult, Parser,
+    ParseError, ParseResult, Parser, Rules,
 };
 
-
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct BestMove(u32, u32);
 
@@ -25,40 +26,172 @@ impl BestMove {
     }
 
     pub fn get_column_notation(&self) -> String {
-        let mut num = self.1;
-        let mut chars = Vec::new();
-        if num == 0 {
-            return "a".to_string();
-        }
-        while num > 0 {
-            let remainder = num % 26;
-            let ch = (b'a' + remainder as u8) as char;
-            chars.push(ch);
-            num /= 26;
-        }
-
-        // Reverse the characters to get the correct order
-        chars.reverse();
-        chars.iter().collect::<String>()
+        column_to_letters(self.1)
     }
 
     pub fn get_row_notation(&self) -> u32 {
         self.0 + 1
     }
+
+    pub fn row(&self) -> u32 {
+        self.0
+    }
+
+    pub fn column(&self) -> u32 {
+        self.1
+    }
 }
 
 impl Display for BestMove {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "best {}{}",
+            "{} {}{}",
+            BEST,
             self.get_column_notation(),
             self.get_row_notation()
         )
     }
 }
 
+const LETTERS: u64 = 26;
+
+/// Columns are lettered bijectively: a..z, then aa..az, ba..zz, aaa and so on.
+pub fn column_to_letters(column: u32) -> String {
+    let mut num = column as u64 + 1;
+    let mut chars = Vec::new();
+    while num > 0 {
+        num -= 1;
+        chars.push((b'a' + (num % LETTERS) as u8) as char);
+        num /= LETTERS;
+    }
+
+    chars.iter().rev().collect::<String>()
+}
+
+/// Inverse of [`column_to_letters`], `None` for anything that is not lowercase letters
+/// or does not fit in a column index.
+pub fn letters_to_column(letters: &str) -> Option<u32> {
+    if letters.is_empty() {
+        return None;
+    }
+
+    letters
+        .chars()
+        .try_fold(0u64, |acc, c| {
+            if !c.is_ascii_lowercase() {
+                return None;
+            }
+            acc.checked_mul(LETTERS)?
+                .checked_add(c as u64 - 'a' as u64 + 1)
+        })
+        .and_then(|column| u32::try_from(column - 1).ok())
+}
+
+pub struct BestTokenParser;
+pub const BEST: &str = "best";
+generate_token_parser!(BEST, BestTokenParser);
+
+/// Parses a coordinate such as `c12` into the zero based row and column it names.
+pub struct CoordinateParser;
+
+impl Parser<BestMove> for CoordinateParser {
+    fn parse_from(val: &str) -> ParseResult<'_, BestMove> {
+        let letters = &val[..val
+            .find(|c: char| !c.is_ascii_lowercase())
+            .unwrap_or(val.len())];
+        let column = letters_to_column(letters).ok_or_else(|| {
+            if letters.is_empty() {
+                ParseError::expected("a column letter", val)
+            } else {
+                ParseError::invalid("column is too large", val)
+            }
+        })?;
+
+        let (row, remaining) = Number::parse_from(&val[letters.len()..])
+            .map_err(|error| error.shift(letters.len()))?;
+        if row.0 == 0 {
+            return Err(
+                ParseError::invalid("rows are numbered from 1", &val[letters.len()..])
+                    .shift(letters.len()),
+            );
+        }
+
+        Ok((BestMove::new(row.0 - 1, column), remaining))
+    }
+}
+
+impl FromStr for BestMove {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        And2::<CoordinateParser, NothingParser>::parse_from(s).map(|((best_move, _), _)| best_move)
+    }
+}
+
+/// Parses an engine's `best <coordinate>` reply.
+pub type BestMoveParser = And3<BestTokenParser, WhiteSpaceParser, CoordinateParser>;
+
+/// Every stone a player places in one turn, which is more than one under rules such as
+/// Connect6. Written as `best <coordinate> <coordinate> ...`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct MultiMove(Vec<BestMove>);
+
+impl MultiMove {
+    pub fn new(moves: Vec<BestMove>) -> Self {
+        Self(moves)
+    }
+
+    pub fn moves(&self) -> &[BestMove] {
+        &self.0
+    }
+}
+
+impl From<BestMove> for MultiMove {
+    fn from(best_move: BestMove) -> Self {
+        Self(vec![best_move])
+    }
+}
+
+impl Display for MultiMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", BEST)?;
+        for best_move in &self.0 {
+            write!(
+                f,
+                " {}{}",
+                best_move.get_column_notation(),
+                best_move.get_row_notation()
+            )?;
+        }
+        Ok(())
+    }
+}
 
+/// Parses a `best` reply with one or more coordinates.
+pub struct MultiMoveParser;
+
+impl Parser<MultiMove> for MultiMoveParser {
+    fn parse_from(val: &str) -> ParseResult<'_, MultiMove> {
+        let ((_, (_, first)), mut remaining) = BestMoveParser::parse_from(val)?;
+        let mut moves = vec![first];
+        while let Ok(((_, best_move), rest)) =
+            And2::<WhiteSpaceParser, CoordinateParser>::parse_from(remaining)
+        {
+            moves.push(best_move);
+            remaining = rest;
+        }
+        Ok((MultiMove(moves), remaining))
+    }
+}
+
+impl FromStr for MultiMove {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        And2::<MultiMoveParser, NothingParser>::parse_from(s).map(|((multi_move, _), _)| multi_move)
+    }
+}
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct BoardState {
@@ -67,6 +200,9 @@ pub struct BoardState {
     pub time_setting: TimeSetting,
     pub win_length: u32,
     pub winner: Option<Player>,
+    pub rules: Rules,
+    /// Stones `player_to_move` has already placed in a turn of several stones.
+    pub placed_this_turn: Vec<BestMove>,
 }
 
 impl BoardState {
@@ -76,42 +212,120 @@ impl BoardState {
         time_setting: TimeSetting,
         win_length: Option<u32>,
     ) -> Self {
-        let default_win_length: u32 = board.clone().rows.len() as u32;
+        let default_win_length: u32 = board.height() as u32;
         Self {
             player_to_move,
             board,
             time_setting,
             win_length: win_length.unwrap_or(default_win_length),
-            winner: None
+            winner: None,
+            rules: Rules::default(),
+            placed_this_turn: Vec::new(),
+        }
+    }
+
+    pub fn with_rules(self, rules: Rules) -> Self {
+        Self {
+            board: self.board.with_topology(rules.topology),
+            rules,
+            ..self
         }
     }
 
     pub fn is_game_over(&self) -> bool {
-        self.board
-            .check_win(self.player_to_move.opponent(), self.win_length)
-            || self.board.check_win(self.player_to_move, self.win_length)
+        self.check_win(self.player_to_move.opponent())
+            || self.check_win(self.player_to_move)
             || self.get_possible_moves().is_empty()
     }
 
-    pub fn get_winner(&mut self) -> Option<Player> {
+    pub fn check_win(&self, player: Player) -> bool {
+        self.board
+            .check_win(player, self.win_length, self.rules.win_rule(player))
+    }
+
+    /// Whether playing `player` at (x, y) still leaves a line along `direction` that can win,
+    /// i.e. it does not make a run that the player's win rule refuses to count.
+    pub fn can_win_along(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+        player: Player,
+    ) -> bool {
+        !self.is_forbidden(x, y, player)
+            && self.run_can_win(&self.board.run_through(x, y, direction, player), player)
+    }
+
+    /// Like [`BoardState::can_win_along`] for a direction in all of the board's dimensions.
+    pub fn can_win_through(&self, x: usize, y: usize, direction: &[isize], player: Player) -> bool {
+        let run = self
+            .board
+            .run_along(&self.board.point_of(x, y), direction, player);
+        !self.is_forbidden(x, y, player) && self.run_can_win(&run, player)
+    }
+
+    fn run_can_win(&self, run: &Run, player: Player) -> bool {
+        run.length < self.win_length
+            || self
+                .rules
+                .win_rule(player)
+                .is_win(run, self.win_length, player)
+    }
+
+    /// Whether the rules forbid `player` from playing at (x, y), as Renju does for X.
+    pub fn is_forbidden(&self, x: usize, y: usize, player: Player) -> bool {
+        self.rules.renju
+            && player == Player::X
+            && self.board.forbidden_move(x, y, self.win_length).is_some()
+    }
 
+    pub fn get_winner(&mut self) -> Option<Player> {
         if self.winner.is_some() {
-            return self.winner
+            return self.winner;
         }
 
-        if self.board.check_win(self.player_to_move.opponent(), self.win_length) {
-            self.winner = Some(self.player_to_move.opponent());
+        if self.check_win(self.player_to_move.opponent()) {
+            self.winner = Some(self.winner_by_line(self.player_to_move.opponent()));
+            return self.winner;
+        }
+
+        if self.check_win(self.player_to_move) {
+            self.winner = Some(self.winner_by_line(self.player_to_move));
             return self.winner;
-        } 
-        
-        if self.board.check_win(self.player_to_move, self.win_length) {
-             self.winner = Some(self.player_to_move);
-             return self.winner;
         }
         None
     }
 
+    /// The player who wins once `player` completes a line: `player` itself, or under
+    /// misère rules the opponent.
+    fn winner_by_line(&self, player: Player) -> Player {
+        if self.rules.misere {
+            player.opponent()
+        } else {
+            player
+        }
+    }
+
     pub fn get_possible_moves(&self) -> Vec<BestMove> {
+        let mut moves = self.get_playable_moves();
+        if self.rules.renju {
+            moves
+                .retain(|mv| !self.is_forbidden(mv.0 as usize, mv.1 as usize, self.player_to_move));
+        }
+        moves
+    }
+
+    fn get_playable_moves(&self) -> Vec<BestMove> {
+        if self.rules.gravity {
+            return (0..self.board.column_count())
+                .flat_map(|j| {
+                    self.board
+                        .drop_rows(j)
+                        .map(move |i| BestMove::new(i as u32, j as u32))
+                })
+                .collect();
+        }
+
         let mut moves = Vec::new();
         let rows = self.board.get_rows();
         for i in 0..rows.len() {
@@ -124,143 +338,380 @@ impl BoardState {
         moves
     }
 
+    /// How many stones `player_to_move` places this turn in all.
+    pub fn stones_this_turn(&self) -> u32 {
+        let opening = self.board.stone_count() == self.placed_this_turn.len();
+        if opening {
+            self.rules.stones_per_turn.first
+        } else {
+            self.rules.stones_per_turn.rest
+        }
+    }
+
+    /// Places a stone for `player_to_move`, handing the turn over once they have placed
+    /// all the stones of their turn.
     pub fn apply_move(&self, mv: &BestMove) -> BoardState {
         let mut new_board = self.board.clone();
-        new_board.play_move(mv.0, mv.1, self.player_to_move.clone());
+        new_board.play_move(mv.0, mv.1, self.player_to_move);
+
+        let mut placed_this_turn = self.placed_this_turn.clone();
+        placed_this_turn.push(mv.clone());
+        let turn_over = placed_this_turn.len() as u32 >= self.stones_this_turn();
+
         BoardState {
-            player_to_move: self.player_to_move.opponent(),
+            player_to_move: if turn_over {
+                self.player_to_move.opponent()
+            } else {
+                self.player_to_move
+            },
             board: new_board,
             time_setting: self.time_setting.clone(),
             win_length: self.win_length,
-            winner: None
+            winner: None,
+            rules: self.rules.clone(),
+            placed_this_turn: if turn_over {
+                Vec::new()
+            } else {
+                placed_this_turn
+            },
         }
     }
+
+    /// Places every stone of `turn`, or says why they are not a legal turn for
+    /// `player_to_move`. Stones after the game is won are ignored.
+    pub fn apply_turn(&self, turn: &MultiMove) -> Result<BoardState, String> {
+        let player = self.player_to_move;
+        let mut state = self.clone();
+        for mv in turn.moves() {
+            if state.player_to_move != player || state.is_game_over() {
+                break;
+            }
+            if !state.get_possible_moves().contains(mv) {
+                return Err(format!("played an illegal move in {}: {}", state, turn));
+            }
+            state = state.apply_move(mv);
+        }
+        if state.player_to_move == player && !state.is_game_over() {
+            return Err(format!("placed too few stones in {}: {}", state, turn));
+        }
+        Ok(state)
+    }
+}
+
+/// Writes the arguments of the `move` command that describes this state.
+impl Display for BoardState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.board, self.player_to_move)?;
+        if self.time_setting != TimeSetting::Infinite {
+            write!(f, " {}", self.time_setting)?;
+        }
+        write!(f, " {} {}", WINL, self.win_length)
+    }
 }
 
 #[cfg(test)]
 mod board_state_tests {
-    use crate::parser::{Board, BoardState, Cell, Player, TimeSetting};
+    use std::str::FromStr;
 
+    use crate::parser::{
+        BestMove, Board, BoardState, Cell, Player, Rules, StonesPerTurn, TimeSetting, WinRule,
+    };
 
     #[test]
     fn test_is_game_over() {
         // Test case 1: Game won by Player X with a horizontal win
-        let board = Board {
-            rows: vec![
-                vec![Cell::Played(Player::X), Cell::Played(Player::X), Cell::Played(Player::X)],
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
+        let board = Board::new(vec![
+            vec![
+                Cell::Played(Player::X),
+                Cell::Played(Player::X),
+                Cell::Played(Player::X),
             ],
-        };
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+        ]);
 
         let board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3));
-        assert!(board_state.is_game_over(), "Game should be over as Player X has won horizontally.");
+        assert!(
+            board_state.is_game_over(),
+            "Game should be over as Player X has won horizontally."
+        );
     }
 
     #[test]
     fn test_draw_no_winner() {
         // Board full with no winner (Draw)
-        let board = Board {
-            rows: vec![
-                vec![Cell::Played(Player::X), Cell::Played(Player::O), Cell::Played(Player::X)],
-                vec![Cell::Played(Player::O), Cell::Played(Player::X), Cell::Played(Player::O)],
-                vec![Cell::Played(Player::O), Cell::Played(Player::X), Cell::Played(Player::O)],
+        let board = Board::new(vec![
+            vec![
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+                Cell::Played(Player::X),
             ],
-        };
+            vec![
+                Cell::Played(Player::O),
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+            ],
+            vec![
+                Cell::Played(Player::O),
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+            ],
+        ]);
 
         let board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3));
-        assert!(board_state.is_game_over(), "Game should be over due to a full board with no winner.");
+        assert!(
+            board_state.is_game_over(),
+            "Game should be over due to a full board with no winner."
+        );
     }
 
     #[test]
     fn test_game_ongoing_with_moves_remaining() {
         // Game ongoing with playable moves and no winner
-        let board = Board {
-            rows: vec![
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
-                vec![Cell::Playable, Cell::Played(Player::X), Cell::Playable],
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
-            ],
-        };
+        let board = Board::new(vec![
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+            vec![Cell::Playable, Cell::Played(Player::X), Cell::Playable],
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+        ]);
 
         let board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3));
-        assert!(!board_state.is_game_over(), "Game should not be over as there are possible moves and no winner.");
+        assert!(
+            !board_state.is_game_over(),
+            "Game should not be over as there are possible moves and no winner."
+        );
     }
 
     #[test]
     fn test_get_winner_player_x_wins() {
         // Player X wins with a horizontal line
-        let board = Board {
-            rows: vec![
-                vec![Cell::Played(Player::X), Cell::Played(Player::X), Cell::Played(Player::X)],
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
+        let board = Board::new(vec![
+            vec![
+                Cell::Played(Player::X),
+                Cell::Played(Player::X),
+                Cell::Played(Player::X),
             ],
-        };
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+        ]);
 
         let mut board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3));
-        assert_eq!(board_state.get_winner(), Some(Player::X), "Player X should be the winner.");
+        assert_eq!(
+            board_state.get_winner(),
+            Some(Player::X),
+            "Player X should be the winner."
+        );
     }
 
-
     #[test]
     fn test_get_diagonal_winner_player_x_wins() {
         // Player X wins with a horizontal line
-        let board = Board {
-            rows: vec![
-                vec![Cell::Played(Player::X), Cell::Played(Player::O), Cell::Played(Player::O)],
-                vec![Cell::Playable, Cell::Played(Player::X), Cell::Playable],
-                vec![Cell::Playable, Cell::Playable, Cell::Played(Player::X)],
+        let board = Board::new(vec![
+            vec![
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+                Cell::Played(Player::O),
             ],
-        };
+            vec![Cell::Playable, Cell::Played(Player::X), Cell::Playable],
+            vec![Cell::Playable, Cell::Playable, Cell::Played(Player::X)],
+        ]);
 
         let mut board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3));
-        assert_eq!(board_state.get_winner(), Some(Player::X), "Player X should be the winner.");
+        assert_eq!(
+            board_state.get_winner(),
+            Some(Player::X),
+            "Player X should be the winner."
+        );
     }
 
     #[test]
     fn test_get_winner_player_o_wins() {
         // Player O wins with a vertical line
-        let board = Board {
-            rows: vec![
-                vec![Cell::Played(Player::O), Cell::Playable, Cell::Playable],
-                vec![Cell::Played(Player::O), Cell::Playable, Cell::Playable],
-                vec![Cell::Played(Player::O), Cell::Playable, Cell::Playable],
-            ],
-        };
+        let board = Board::new(vec![
+            vec![Cell::Played(Player::O), Cell::Playable, Cell::Playable],
+            vec![Cell::Played(Player::O), Cell::Playable, Cell::Playable],
+            vec![Cell::Played(Player::O), Cell::Playable, Cell::Playable],
+        ]);
 
         let mut board_state = BoardState::new(Player::O, board, TimeSetting::Infinite, Some(3));
-        assert_eq!(board_state.get_winner(), Some(Player::O), "Player O should be the winner.");
+        assert_eq!(
+            board_state.get_winner(),
+            Some(Player::O),
+            "Player O should be the winner."
+        );
     }
 
     #[test]
     fn test_get_winner_draw_no_winner() {
         // Full board with no winner (draw)
-        let board = Board {
-            rows: vec![
-                vec![Cell::Played(Player::X), Cell::Played(Player::O), Cell::Played(Player::X)],
-                vec![Cell::Played(Player::O), Cell::Played(Player::X), Cell::Played(Player::O)],
-                vec![Cell::Played(Player::O), Cell::Played(Player::X), Cell::Played(Player::O)],
+        let board = Board::new(vec![
+            vec![
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+                Cell::Played(Player::X),
             ],
-        };
+            vec![
+                Cell::Played(Player::O),
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+            ],
+            vec![
+                Cell::Played(Player::O),
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+            ],
+        ]);
 
         let mut board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3));
-        assert_eq!(board_state.get_winner(), None, "There should be no winner in a draw.");
+        assert_eq!(
+            board_state.get_winner(),
+            None,
+            "There should be no winner in a draw."
+        );
     }
 
     #[test]
     fn test_get_winner_ongoing_game_no_winner() {
         // Game ongoing with no winner yet
-        let board = Board {
-            rows: vec![
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
-                vec![Cell::Playable, Cell::Played(Player::X), Cell::Playable],
-                vec![Cell::Playable, Cell::Playable, Cell::Playable],
-            ],
-        };
+        let board = Board::new(vec![
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+            vec![Cell::Playable, Cell::Played(Player::X), Cell::Playable],
+            vec![Cell::Playable, Cell::Playable, Cell::Playable],
+        ]);
 
         let mut board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3));
-        assert_eq!(board_state.get_winner(), None, "There should be no winner as the game is ongoing.");
+        assert_eq!(
+            board_state.get_winner(),
+            None,
+            "There should be no winner as the game is ongoing."
+        );
+    }
+
+    #[test]
+    fn test_gravity_moves_land_on_lowest_playable_cell() {
+        let board = Board::from_str("_x_/_x_/ox_").unwrap();
+
+        let board_state = BoardState::new(Player::O, board, TimeSetting::Infinite, Some(3))
+            .with_rules(Rules {
+                gravity: true,
+                ..Rules::default()
+            });
+        assert_eq!(
+            board_state.get_possible_moves(),
+            vec![BestMove::new(1, 0), BestMove::new(2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_gravity_is_kept_after_move() {
+        let board = Board::from_str("3_/3_/3_").unwrap();
+
+        let board_state = BoardState::new(Player::X, board, TimeSetting::Infinite, Some(3))
+            .with_rules(Rules {
+                gravity: true,
+                ..Rules::default()
+            })
+            .apply_move(&BestMove::new(2, 1));
+        assert_eq!(
+            board_state.get_possible_moves(),
+            vec![
+                BestMove::new(2, 0),
+                BestMove::new(1, 1),
+                BestMove::new(2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_turn_passes_after_all_its_stones() {
+        let rules = Rules {
+            stones_per_turn: StonesPerTurn { first: 1, rest: 2 },
+            ..Rules::default()
+        };
+        let board_state = BoardState::new(
+            Player::X,
+            Board::from_str("5_/5_/5_/5_/5_").unwrap(),
+            TimeSetting::Infinite,
+            Some(5),
+        )
+        .with_rules(rules);
+        assert_eq!(board_state.stones_this_turn(), 1);
+
+        let board_state = board_state.apply_move(&BestMove::new(2, 2));
+        assert_eq!(board_state.player_to_move, Player::O);
+        assert_eq!(board_state.stones_this_turn(), 2);
+
+        let board_state = board_state.apply_move(&BestMove::new(1, 1));
+        assert_eq!(board_state.player_to_move, Player::O);
+        assert_eq!(board_state.placed_this_turn, vec![BestMove::new(1, 1)]);
+
+        let board_state = board_state.apply_move(&BestMove::new(1, 2));
+        assert_eq!(board_state.player_to_move, Player::X);
+        assert!(board_state.placed_this_turn.is_empty());
+        assert_eq!(board_state.stones_this_turn(), 2);
+    }
+
+    #[test]
+    fn test_completing_a_line_loses_under_misere() {
+        let board = Board::from_str("xxx/oo_/3_").unwrap();
+        let rules = Rules {
+            misere: true,
+            ..Rules::default()
+        };
+
+        let mut board_state =
+            BoardState::new(Player::O, board, TimeSetting::Infinite, Some(3)).with_rules(rules);
+        assert!(board_state.is_game_over());
+        assert_eq!(board_state.get_winner(), Some(Player::O));
+    }
+
+    #[test]
+    fn test_overline_does_not_win_exact_length() {
+        let rules = Rules {
+            x_win_rule: WinRule::ExactLength,
+            ..Rules::default()
+        };
+
+        let board = Board::from_str("6x/6_/3o3_/6_/o5_/6_").unwrap();
+        let mut board_state = BoardState::new(Player::O, board, TimeSetting::Infinite, Some(5))
+            .with_rules(rules.clone());
+        assert_eq!(board_state.get_winner(), None);
+
+        let board = Board::from_str("4x_x/6_/3o3_/6_/o5_/6_").unwrap();
+        let board_state =
+            BoardState::new(Player::X, board, TimeSetting::Infinite, Some(5)).with_rules(rules);
+        assert!(!board_state.can_win_along(0, 4, (0, 1), Player::X));
+    }
+
+    #[test]
+    fn test_win_rules_apply_per_player() {
+        let board = Board::from_str("6o/6_/6x/6_/x5_/6_").unwrap();
+        let rules = Rules {
+            x_win_rule: WinRule::ExactLength,
+            ..Rules::default()
+        };
+
+        let mut board_state =
+            BoardState::new(Player::X, board, TimeSetting::Infinite, Some(5)).with_rules(rules);
+        assert_eq!(board_state.get_winner(), Some(Player::O));
+    }
+
+    #[test]
+    fn test_caro_five_blocked_at_both_ends_does_not_win() {
+        let rules = Rules {
+            x_win_rule: WinRule::Caro,
+            o_win_rule: WinRule::Caro,
+            ..Rules::default()
+        };
+
+        let board = Board::from_str("o5xo_/8_/8_/8_/o7_/8_/8_/8_").unwrap();
+        let mut blocked = BoardState::new(Player::O, board, TimeSetting::Infinite, Some(5))
+            .with_rules(rules.clone());
+        assert_eq!(blocked.get_winner(), None);
+
+        let board = Board::from_str("_5xo_/8_/8_/8_/o7_/o7_/8_/8_").unwrap();
+        let mut open =
+            BoardState::new(Player::O, board, TimeSetting::Infinite, Some(5)).with_rules(rules);
+        assert_eq!(open.get_winner(), Some(Player::X));
     }
 }
 
@@ -308,112 +759,23 @@ impl FromStr for Player {
     }
 }
 
-pub struct PlayerParser;
-
-impl Parser<Player> for PlayerParser {
-    fn parse_from(val: &String) -> ParseResult<Player> {
-        if val.is_empty() {
-            return Err("Expected a non empty string".to_string());
+impl Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Player::X => write!(f, "x"),
+            Player::O => write!(f, "o"),
         }
-        Ok((Player::from_str(&val[..1])?, val[1..].to_string()))
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct Board {
-    rows: Vec<Vec<Cell>>,
-}
-
-impl Board {
-    pub fn new(rows: Vec<Vec<Cell>>) -> Self {
-        Self { rows }
-    }
-
-    pub fn get_rows(&self) -> Vec<Vec<Cell>> {
-        self.rows.clone()
-    }
-
-    pub fn play_move(&mut self, x: u32, y: u32, player: Player) {
-        if let Some(row) = self.rows.get_mut(x as usize) {
-            if let Some(cell) = row.get_mut(y as usize) {
-                *cell = Cell::Played(player);
-            }
-        }
-    }
-
-    pub fn check_win(&self, player: Player, win_length: u32) -> bool {
-        let rows = self.get_rows();
-        let rlen = rows.len();
-        let clen = rows[0].len();
-
-        // Check rows
-        for row in rows {
-            let mut count = 0;
-            for cell in row {
-                if cell == Cell::Played(player.clone()) {
-                    count += 1;
-                    if count >= win_length {
-                        return true;
-                    }
-                } else {
-                    count = 0;
-                }
-            }
-        }
-
-        // Check columns
-        for col in 0..clen {
-            let mut count = 0;
-            for row in 0..rlen {
-                if self.rows[row][col] == Cell::Played(player.clone()) {
-                    count += 1;
-                    if count >= win_length {
-                        return true;
-                    }
-                } else {
-                    count = 0;
-                }
-            }
-        }
-
-        // Check diagonals
-        for row in 0..rlen {
-            for col in 0..clen {
-                if self.rows[row][col] == Cell::Played(player.clone()) {
-                    // Check diagonal down-right
-                    if row + win_length as usize <= rlen && col + win_length as usize <= clen {
-                        let mut count = 1;
-                        for i in 1..win_length as usize {
-                            if self.rows[row + i][col + i] == Cell::Played(player.clone()) {
-                                count += 1;
-                            } else {
-                                break;
-                            }
-                        }
-                        if count >= win_length as usize {
-                            return true;
-                        }
-                    }
-
-                    // Check diagonal up-right
-                    if row >= (win_length as usize - 1) && col + win_length as usize <= clen {
-                        let mut count = 1;
-                        for i in 1..win_length as usize {
-                            if self.rows[row - i][col + i] == Cell::Played(player.clone()) {
-                                count += 1;
-                            } else {
-                                break;
-                            }
-                        }
-                        if count >= win_length as usize {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
+pub struct PlayerParser;
 
-        false
+impl Parser<Player> for PlayerParser {
+    fn parse_from(val: &str) -> ParseResult<'_, Player> {
+        val.get(..1)
+            .and_then(|player| Player::from_str(player).ok())
+            .map(|player| (player, &val[1..]))
+            .ok_or_else(|| ParseError::expected_one_of(&["x", "o"], val))
     }
 }
 
@@ -431,37 +793,50 @@ impl FromStr for Cell {
     }
 }
 
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cell::Playable => write!(f, "_"),
+            Cell::NonPlayable => write!(f, "."),
+            Cell::Played(player) => write!(f, "{}", player),
+        }
+    }
+}
+
+const CELLS: [&str; 4] = ["_", ".", "x", "o"];
+
 impl Cell {
-    fn from_row(row: &str) -> Result<Vec<Cell>, String> {
+    fn from_row(row: &str) -> Result<Vec<Cell>, ParseError> {
         let mut board_row: Vec<Cell> = vec![];
 
-        let mut iter = row.chars().peekable();
+        let mut iter = row.char_indices().peekable();
 
-        while let Some(c) = iter.next() {
+        while let Some((start, c)) = iter.next() {
             match c {
                 '1'..='9' => {
-                    let mut numric_string: String = c.to_string();
-                    while let Some(&next_char) = iter.peek() {
+                    let mut end = start + 1;
+                    while let Some(&(next, next_char)) = iter.peek() {
                         if next_char.is_numeric() {
-                            numric_string.push(next_char);
+                            end = next + next_char.len_utf8();
                             iter.next();
                         } else {
                             break;
                         }
                     }
-                    let number = Number::try_from(&numric_string)?;
+                    let number = Number::try_from(&row[start..end]).map_err(|reason| {
+                        ParseError::invalid(reason, &row[start..]).shift(start)
+                    })?;
 
-                    if let Some(x) = iter.peek() {
+                    if let Some((offset, x)) = iter.next() {
                         board_row.extend(
-                            repeat(Cell::from_str(&x.to_string())?).take((number.0) as usize),
+                            repeat(Cell::from_char(row, offset, x)?).take((number.0) as usize),
                         );
-                        iter.next();
                     } else {
-                        return Err(String::from("Invalid row, row can't end with a number"));
+                        return Err(ParseError::expected_one_of(&CELLS, "").shift(row.len()));
                     }
                 }
                 _ => {
-                    let cell = Cell::from_str(&c.to_string())?;
+                    let cell = Cell::from_char(row, start, c)?;
                     board_row.push(cell);
                 }
             }
@@ -469,90 +844,207 @@ impl Cell {
 
         Ok(board_row)
     }
+
+    fn from_char(row: &str, offset: usize, c: char) -> Result<Cell, ParseError> {
+        let cell = &row[offset..offset + c.len_utf8()];
+        Cell::from_str(cell).map_err(|_| ParseError::expected_one_of(&CELLS, cell).shift(offset))
+    }
 }
 
 impl FromStr for Board {
-    type Err = String;
+    type Err = ParseError;
 
+    /// Reads rows separated by `/`. Boards of more dimensions list their layers separated
+    /// by `|`, and their layers' layers by `||` and so on, e.g. `4_/4_|4_/4_` for 2x2x4.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rows: Vec<Vec<Cell>> = s
-            .split('/')
-            .map(|row| Cell::from_row(row))
-            .collect::<Result<Vec<Vec<Cell>>, String>>()?;
+        let separator = LAYER_SEPARATOR.to_string().repeat(deepest_separator(s));
+        if separator.is_empty() {
+            let mut rows: Vec<Vec<Cell>> = vec![];
+            let mut offset = 0;
+            for row in s.split('/') {
+                rows.push(Cell::from_row(row).map_err(|error| error.shift(offset))?);
+                offset += row.len() + 1;
+            }
+
+            return Ok(Board::new(rows));
+        }
 
-        Ok(Board { rows })
+        let mut layers: Vec<Board> = vec![];
+        let mut offset = 0;
+        for layer in s.split(separator.as_str()) {
+            let board = Board::from_str(layer).map_err(|error| error.shift(offset))?;
+            if let Some(first) = layers.first() {
+                if (board.rows.len(), &board.layers) != (first.rows.len(), &first.layers) {
+                    return Err(ParseError::invalid("layers differ in size", layer).shift(offset));
+                }
+            }
+            layers.push(board);
+            offset += layer.len() + separator.len();
+        }
+
+        Ok(Board::stack(layers))
+    }
+}
+
+/// Writes the board in canonical T3N, with every run of two or more equal cells
+/// compressed to `<count><cell>`.
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = self.height().max(1);
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 && i % height == 0 {
+                // One separator for each dimension whose layer changes here.
+                let mut layer = i / height;
+                let mut depth = 1;
+                for size in self.layers.iter().skip(1).rev() {
+                    if !layer.is_multiple_of(*size) {
+                        break;
+                    }
+                    layer /= size;
+                    depth += 1;
+                }
+                write!(f, "{}", LAYER_SEPARATOR.to_string().repeat(depth))?;
+            } else if i > 0 {
+                write!(f, "/")?;
+            }
+            let mut cells = row.iter().peekable();
+            while let Some(cell) = cells.next() {
+                let mut run = 1;
+                while cells.next_if_eq(&cell).is_some() {
+                    run += 1;
+                }
+                if run > 1 {
+                    write!(f, "{}", run)?;
+                }
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
     }
 }
 
+const LAYER_SEPARATOR: char = '|';
+
+/// The longest run of layer separators in a board, which splits its outermost layers.
+fn deepest_separator(board: &str) -> usize {
+    board
+        .split(|c| c != LAYER_SEPARATOR)
+        .map(|separators| separators.len())
+        .max()
+        .unwrap_or(0)
+}
+
 pub struct BoardParser;
 
 impl Parser<Board> for BoardParser {
-    fn parse_from(val: &String) -> ParseResult<Board> {
+    fn parse_from(val: &str) -> ParseResult<'_, Board> {
         if let Some((board_string, rest)) = val.split_once(' ') {
-            return Ok((Board::from_str(board_string)?, rest.to_string()));
+            return Ok((Board::from_str(board_string)?, rest));
         }
-        Err("Invalid T3N notation".to_string())
+        Board::from_str(val)?;
+        Err(ParseError::expected(" ", "").shift(val.len()))
     }
 }
 pub type T3NParser = And2<BoardParser, PlayerParser>;
 
 pub type BasicMoveParser = And3<MoveTokenParser, WhiteSpaceParser, T3NParser>;
 
-pub type MoveWithInfiniteTime = And2<BasicMoveParser, AnythingParser>;
-pub type MoveWithTotalTime = And3<BasicMoveParser, WhiteSpaceParser, TotalTimeParser>;
-pub type MoveWithTimeRemaining = And3<BasicMoveParser, WhiteSpaceParser, TimeRemainingParser>;
-
-pub type MoveParser = And2<
-    Or3<MoveWithTotalTime, MoveWithTimeRemaining, MoveWithInfiniteTime>,
-    Or2<And2<WhiteSpaceParser, WinLengthParser>, NothingParser>,
->;
-pub type MoveParserReturnType = (
-    Either<
-        (
-            (String, (String, (Board, Player))),
-            (String, (String, (String, Number))),
-        ),
-        Either<
-            (
-                (String, (String, (Board, Player))),
-                (String, (String, (String, Number))),
-            ),
-            ((String, (String, (Board, Player))), Anything),
-        >,
-    >,
-    Either<(String, (String, (String, Number))), Nothing>,
-);
+pub type MoveOptionParser =
+    Or5<TotalTimeParser, TimeRemainingParser, IncrementParser, MovesToGoParser, WinLengthParser>;
 
-impl From<MoveParserReturnType> for BoardState {
-    fn from(value: MoveParserReturnType) -> Self {
-        match value {
-            (Either::Left(((_, (_, (board, player))), (_, (_, (_, time))))), win_length) => {
-                BoardState::new(
-                    player,
-                    board,
-                    TimeSetting::TotalTime(time),
-                    win_length.map_left(|(_, (_, (_, number)))| number.0).left(),
+/// The trailing `key value` arguments of a `move` command, which may come in any order.
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct MoveOptions {
+    pub time_setting: Option<TimeSetting>,
+    pub win_length: Option<u32>,
+}
+
+pub struct MoveOptionsParser;
+
+const TIME_SETTING: &str = "time setting";
+
+impl Parser<MoveOptions> for MoveOptionsParser {
+    fn parse_from(val: &str) -> ParseResult<'_, MoveOptions> {
+        let mut options = MoveOptions::default();
+        let mut increment = None;
+        let mut moves_to_go = None;
+        let mut clock_offset = None;
+        let mut remaining = val;
+
+        while NothingParser::parse_from(remaining).is_err() {
+            let offset = val.len() - remaining.len();
+            let ((_, option), rest) =
+                And2::<WhiteSpaceParser, MoveOptionParser>::parse_from(remaining)
+                    .map_err(|error| error.shift(offset))?;
+
+            let duplicate = match option {
+                Either::Left((_, (_, time))) => options
+                    .time_setting
+                    .replace(TimeSetting::TotalTime(time))
+                    .map(|_| TIME_SETTING),
+                Either::Right(Either::Left((_, (_, time)))) => options
+                    .time_setting
+                    .replace(TimeSetting::TimeRemaining(time))
+                    .map(|_| TIME_SETTING),
+                Either::Right(Either::Right(Either::Left((key, (_, time))))) => {
+                    clock_offset.get_or_insert(offset + 1);
+                    increment.replace(time).map(|_| key)
+                }
+                Either::Right(Either::Right(Either::Right(Either::Left((key, (_, moves)))))) => {
+                    clock_offset.get_or_insert(offset + 1);
+                    moves_to_go.replace(moves).map(|_| key)
+                }
+                Either::Right(Either::Right(Either::Right(Either::Right((
+                    key,
+                    (_, win_length),
+                ))))) => options.win_length.replace(win_length.0).map(|_| key),
+            };
+
+            if let Some(option) = duplicate {
+                return Err(ParseError::invalid(
+                    format!("{} given more than once", option),
+                    &remaining[1..],
                 )
+                .shift(offset + 1));
             }
-            (
-                Either::Right(Either::Left(((_, (_, (board, player))), (_, (_, (_, time)))))),
-                win_length,
-            ) => BoardState::new(
-                player,
-                board,
-                TimeSetting::TimeRemaining(time),
-                win_length.map_left(|(_, (_, (_, number)))| number.0).left(),
-            ),
-
-            (Either::Right(Either::Right(((_, (_, (board, player))), _))), win_length) => {
-                BoardState::new(
-                    player,
-                    board,
-                    TimeSetting::Infinite,
-                    win_length.map_left(|(_, (_, (_, number)))| number.0).left(),
-                )
+            remaining = rest;
+        }
+
+        if let Some(offset) = clock_offset {
+            match options.time_setting {
+                Some(TimeSetting::TimeRemaining(time)) => {
+                    options.time_setting = Some(TimeSetting::Clock {
+                        remaining: time,
+                        increment: increment.unwrap_or(Number(0)),
+                        moves_to_go,
+                    })
+                }
+                _ => {
+                    return Err(ParseError::invalid(
+                        "increment and moves-to-go need time-remaining",
+                        &val[offset..],
+                    )
+                    .shift(offset))
+                }
             }
         }
+
+        Ok((options, remaining))
+    }
+}
+
+pub type MoveParser = And2<BasicMoveParser, MoveOptionsParser>;
+pub type MoveParserReturnType = ((&'static str, (&'static str, (Board, Player))), MoveOptions);
+
+impl From<MoveParserReturnType> for BoardState {
+    fn from(value: MoveParserReturnType) -> Self {
+        let ((_, (_, (board, player))), options) = value;
+        BoardState::new(
+            player,
+            board,
+            options.time_setting.unwrap_or(TimeSetting::Infinite),
+            options.win_length,
+        )
     }
 }
 
@@ -572,25 +1064,23 @@ mod test_t3nparser {
 
     #[test]
     fn parse_t3n_board() {
-        let board_string = String::from("3_.x/4_o/5. x time-remaining ms:1500ms");
-        let board = BoardParser::parse_from(&board_string);
+        let board_string = "3_.x/4_o/5. x time-remaining ms:1500ms";
+        let board = BoardParser::parse_from(board_string);
 
         assert_eq!(
             Ok((
-                Board {
-                    rows: vec![
-                        vec![Playable, Playable, Playable, NonPlayable, Played(Player::X)],
-                        vec![Playable, Playable, Playable, Playable, Played(Player::O)],
-                        vec![
-                            NonPlayable,
-                            NonPlayable,
-                            NonPlayable,
-                            NonPlayable,
-                            NonPlayable
-                        ]
+                Board::new(vec![
+                    vec![Playable, Playable, Playable, NonPlayable, Played(Player::X)],
+                    vec![Playable, Playable, Playable, Playable, Played(Player::O)],
+                    vec![
+                        NonPlayable,
+                        NonPlayable,
+                        NonPlayable,
+                        NonPlayable,
+                        NonPlayable
                     ]
-                },
-                String::from("x time-remaining ms:1500ms")
+                ]),
+                "x time-remaining ms:1500ms"
             )),
             board
         )
@@ -599,7 +1089,7 @@ mod test_t3nparser {
     #[test]
     fn parse_t3n_row() {
         let row_string = "5_";
-        let board_row = Cell::from_row(&row_string);
+        let board_row = Cell::from_row(row_string);
         assert_eq!(
             Ok(vec![Playable, Playable, Playable, Playable, Playable]),
             board_row
@@ -608,179 +1098,432 @@ mod test_t3nparser {
 }
 
 #[cfg(test)]
-mod test_move_parser {
-    use either::Either::{Left, Right};
+mod test_t3n_serializer {
+    use std::str::FromStr;
+
+    use crate::parser::{number_parser::Number, Command};
+
+    use super::{Board, BoardState, Cell, Player, TimeSetting};
+
+    /// Small xorshift generator so the round-trip cases are reproducible.
+    struct Cases(u64);
+
+    impl Cases {
+        fn next(&mut self, bound: u32) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as u32
+        }
+
+        fn board(&mut self) -> Board {
+            let (rlen, clen) = (1 + self.next(12), 1 + self.next(30));
+            let cells = [
+                Cell::Playable,
+                Cell::NonPlayable,
+                Cell::Played(Player::X),
+                Cell::Played(Player::O),
+            ];
+            // Bias towards repeating the previous cell so long runs get exercised too.
+            let rows = (0..rlen)
+                .map(|_| {
+                    let mut cell = cells[self.next(4) as usize];
+                    (0..clen)
+                        .map(|_| {
+                            if self.next(3) == 0 {
+                                cell = cells[self.next(4) as usize];
+                            }
+                            cell
+                        })
+                        .collect()
+                })
+                .collect();
+            Board::new(rows)
+        }
+
+        fn time_setting(&mut self) -> TimeSetting {
+            match self.next(4) {
+                0 => TimeSetting::Infinite,
+                1 => TimeSetting::TotalTime(Number(self.next(100000))),
+                2 => TimeSetting::TimeRemaining(Number(self.next(100000))),
+                _ => TimeSetting::Clock {
+                    remaining: Number(self.next(100000)),
+                    increment: Number(self.next(1000)),
+                    moves_to_go: Some(Number(self.next(50))).filter(|n| n.0 > 0),
+                },
+            }
+        }
+    }
 
-    use crate::parser::{anything_parser::Anything, nothing_parser::Nothing, Parser};
+    #[test]
+    fn writes_run_length_compressed_rows() {
+        let board = Board::from_str("3_.x/4_o/5.").unwrap();
+        assert_eq!("3_.x/4_o/5.", board.to_string());
+    }
+
+    #[test]
+    fn writes_single_cells_without_count() {
+        let board = Board::from_str("1_1x2o/_x_/10.").unwrap();
+        assert_eq!("_x2o/_x_/10.", board.to_string());
+    }
+
+    #[test]
+    fn writes_move_arguments() {
+        let board_state = BoardState::new(
+            Player::O,
+            Board::from_str("3_/_x_/3_").unwrap(),
+            TimeSetting::TimeRemaining(Number(1500)),
+            Some(3),
+        );
+        assert_eq!(
+            "3_/_x_/3_ o time-remaining ms:1500 win-length 3",
+            board_state.to_string()
+        );
+    }
+
+    #[test]
+    fn board_round_trips_through_parser() {
+        let mut cases = Cases(0x5eed);
+        for _ in 0..500 {
+            let board = cases.board();
+            assert_eq!(Ok(board.clone()), Board::from_str(&board.to_string()));
+        }
+    }
+
+    #[test]
+    fn board_state_round_trips_through_parser() {
+        let mut cases = Cases(0xb0a2d);
+        for _ in 0..500 {
+            let player = [Player::X, Player::O][cases.next(2) as usize];
+            let board_state = BoardState::new(
+                player,
+                cases.board(),
+                cases.time_setting(),
+                Some(1 + cases.next(10)),
+            );
+            let command = format!("move {}", board_state);
+            assert_eq!(Ok(Command::Move(board_state)), command.parse::<Command>());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_move_parser {
+    use crate::parser::Parser;
 
     use super::*;
 
     use Cell::{Playable, Played};
 
+    fn empty_board() -> Board {
+        Board::new(vec![
+            vec![Playable, Playable, Playable],
+            vec![Playable, Playable, Playable],
+            vec![Playable, Playable, Playable],
+        ])
+    }
+
     #[test]
     fn parse_move_without_time() {
-        let move_string = String::from("move 3_/_x_/3_ o");
+        let move_string = "move 3_/_x_/3_ o";
 
-        let res = MoveParser::parse_from(&move_string);
+        let res = MoveParser::parse_from(move_string);
 
         assert_eq!(
             res,
             Ok((
                 (
-                    Right(Right((
-                        ((
-                            String::from("move"),
+                    (
+                        "move",
+                        (
+                            " ",
                             (
-                                String::from(" "),
-                                (
-                                    Board {
-                                        rows: vec![
-                                            vec![Playable, Playable, Playable],
-                                            vec![Playable, Played(Player::X), Playable],
-                                            vec![Playable, Playable, Playable]
-                                        ]
-                                    },
-                                    Player::O
-                                )
+                                Board::new(vec![
+                                    vec![Playable, Playable, Playable],
+                                    vec![Playable, Played(Player::X), Playable],
+                                    vec![Playable, Playable, Playable]
+                                ]),
+                                Player::O
                             )
-                        )),
-                        Anything::new("".to_string())
-                    ))),
-                    Right(Nothing)
+                        )
+                    ),
+                    MoveOptions::default()
                 ),
-                String::from(""),
+                "",
             ))
         );
     }
 
     #[test]
     fn parse_move_with_total_time() {
-        let move_string = String::from("move 3_/3_/3_ x time ms:1000");
+        let move_string = "move 3_/3_/3_ x time ms:1000";
 
-        let res = MoveParser::parse_from(&move_string);
+        let res = MoveParser::parse_from(move_string);
 
         assert_eq!(
             res,
             Ok((
                 (
-                    Left((
-                        ((
-                            String::from("move"),
-                            (
-                                " ".to_string(),
-                                (
-                                    Board {
-                                        rows: vec![
-                                            vec![Playable, Playable, Playable],
-                                            vec![Playable, Playable, Playable],
-                                            vec![Playable, Playable, Playable]
-                                        ]
-                                    },
-                                    Player::X
-                                )
-                            )
-                        )),
-                        (
-                            " ".to_string(),
-                            ("time".to_string(), (" ".to_string(), Number(1000)))
-                        )
-                    )),
-                    Right(Nothing)
+                    ("move", (" ", (empty_board(), Player::X))),
+                    MoveOptions {
+                        time_setting: Some(TimeSetting::TotalTime(Number(1000))),
+                        win_length: None
+                    }
                 ),
-                "".to_string()
+                ""
             ))
         );
     }
 
     #[test]
     fn parse_move_with_remaining_time() {
-        let move_string = String::from("move 3_/3_/3_ x time-remaining ms:10000");
+        let move_string = "move 3_/3_/3_ x time-remaining ms:10000";
 
-        let res = MoveParser::parse_from(&move_string);
+        let res = MoveParser::parse_from(move_string);
 
         assert_eq!(
             res,
             Ok((
                 (
-                    Right(Left((
-                        ((
-                            String::from("move"),
-                            (
-                                " ".to_string(),
-                                (
-                                    Board {
-                                        rows: vec![
-                                            vec![Playable, Playable, Playable],
-                                            vec![Playable, Playable, Playable],
-                                            vec![Playable, Playable, Playable]
-                                        ]
-                                    },
-                                    Player::X
-                                )
-                            )
-                        )),
-                        (
-                            " ".to_string(),
-                            (
-                                "time-remaining".to_string(),
-                                (" ".to_string(), Number(10000))
-                            )
-                        )
-                    ))),
-                    Right(Nothing)
+                    ("move", (" ", (empty_board(), Player::X))),
+                    MoveOptions {
+                        time_setting: Some(TimeSetting::TimeRemaining(Number(10000))),
+                        win_length: None
+                    }
                 ),
-                "".to_string()
+                ""
             ))
         );
     }
 
     #[test]
     fn parse_move_with_win_length() {
-        let move_string = String::from("move 3_/3_/3_ x win-length 3");
+        let move_string = "move 3_/3_/3_ x win-length 3";
 
-        let res = MoveParser::parse_from(&move_string);
+        let res = MoveParser::parse_from(move_string);
 
         assert_eq!(
             res,
             Ok((
                 (
-                    Right(Right((
-                        ((
-                            String::from("move"),
-                            (
-                                String::from(" "),
-                                (
-                                    Board {
-                                        rows: vec![
-                                            vec![Playable, Playable, Playable],
-                                            vec![Playable, Playable, Playable],
-                                            vec![Playable, Playable, Playable]
-                                        ]
-                                    },
-                                    Player::X
-                                )
-                            )
-                        )),
-                        Anything::new(" win-length 3".to_string())
-                    ))),
-                    Left((
-                        " ".to_string(),
-                        ("win-length".to_string(), (" ".to_string(), Number(3)))
-                    ))
+                    ("move", (" ", (empty_board(), Player::X))),
+                    MoveOptions {
+                        time_setting: None,
+                        win_length: Some(3)
+                    }
                 ),
-                String::from(""),
+                ""
             ))
         );
     }
+
+    #[test]
+    fn parse_move_options_in_any_order() {
+        let time_first = "move 3_/3_/3_ x time-remaining ms:500 win-length 3";
+        let win_length_first = "move 3_/3_/3_ x win-length 3 time-remaining ms:500";
+
+        for move_string in [time_first, win_length_first] {
+            let res = MoveParser::parse_from(move_string);
+
+            assert_eq!(
+                res.map(|((_, options), _)| options),
+                Ok(MoveOptions {
+                    time_setting: Some(TimeSetting::TimeRemaining(Number(500))),
+                    win_length: Some(3),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn err_on_unknown_option() {
+        let move_string = "move 3_/3_/3_ x win-length 3 delay ms:10";
+
+        let res = MoveParser::parse_from(move_string);
+
+        assert_eq!(
+            res,
+            Err(ParseError::expected_one_of(
+                &[
+                    "time",
+                    "time-remaining",
+                    "increment",
+                    "moves-to-go",
+                    "win-length"
+                ],
+                "delay"
+            )
+            .shift(29))
+        );
+    }
+
+    #[test]
+    fn err_on_duplicate_option() {
+        let move_string = "move 3_/3_/3_ x win-length 3 win-length 4";
+
+        let res = MoveParser::parse_from(move_string);
+
+        assert_eq!(
+            res,
+            Err(ParseError::invalid("win-length given more than once", "win-length").shift(29))
+        );
+    }
+
+    #[test]
+    fn err_on_conflicting_time_settings() {
+        let move_string = "move 3_/3_/3_ x time ms:100 time-remaining ms:100";
+
+        let res = MoveParser::parse_from(move_string);
+
+        assert_eq!(
+            res,
+            Err(
+                ParseError::invalid("time setting given more than once", "time-remaining")
+                    .shift(28)
+            )
+        );
+    }
+
+    #[test]
+    fn parse_move_with_clock() {
+        let move_string = "move 3_/3_/3_ x moves-to-go 40 time-remaining m:5 increment s:3";
+
+        let res = MoveParser::parse_from(move_string);
+
+        assert_eq!(
+            res.map(|((_, options), _)| options),
+            Ok(MoveOptions {
+                time_setting: Some(TimeSetting::Clock {
+                    remaining: Number(300000),
+                    increment: Number(3000),
+                    moves_to_go: Some(Number(40)),
+                }),
+                win_length: None,
+            })
+        );
+    }
+
+    #[test]
+    fn err_on_increment_without_time_remaining() {
+        let move_string = "move 3_/3_/3_ x time s:1 increment s:1";
+
+        let res = MoveParser::parse_from(move_string);
+
+        assert_eq!(
+            res,
+            Err(
+                ParseError::invalid("increment and moves-to-go need time-remaining", "increment")
+                    .shift(25)
+            )
+        );
+    }
 }
 
 #[cfg(test)]
 mod test_best_move {
-    use crate::parser::move_parser::BestMove;
+    use std::str::FromStr;
+
+    use crate::parser::{
+        move_parser::{column_to_letters, letters_to_column, BestMove, BestMoveParser, MultiMove},
+        ParseError, Parser,
+    };
 
     #[test]
     fn test_column_notation() {
         let best_move = BestMove::new(0, 0);
         assert_eq!("a", best_move.get_column_notation());
     }
+
+    #[test]
+    fn test_wide_column_notation() {
+        assert_eq!("z", column_to_letters(25));
+        assert_eq!("aa", column_to_letters(26));
+        assert_eq!("ad", column_to_letters(29));
+        assert_eq!("zz", column_to_letters(701));
+        assert_eq!("aaa", column_to_letters(702));
+    }
+
+    #[test]
+    fn column_letters_round_trip() {
+        for column in 0..20000 {
+            assert_eq!(Some(column), letters_to_column(&column_to_letters(column)));
+        }
+        assert_eq!(
+            Some(u32::MAX),
+            letters_to_column(&column_to_letters(u32::MAX))
+        );
+    }
+
+    #[test]
+    fn column_letters_are_ordered_by_length_then_alphabet() {
+        let columns = (0..1000).map(column_to_letters).collect::<Vec<String>>();
+        for pair in columns.windows(2) {
+            assert!((pair[0].len(), &pair[0]) < (pair[1].len(), &pair[1]));
+        }
+    }
+
+    #[test]
+    fn moves_round_trip_through_display() {
+        for row in 0..40 {
+            for column in 0..800 {
+                let best_move = BestMove::new(row, column);
+                let reply = best_move.to_string();
+                assert_eq!(
+                    Ok((("best", (" ", best_move)), "")),
+                    BestMoveParser::parse_from(&reply)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse_coordinate() {
+        assert_eq!(Ok(BestMove::new(11, 27)), BestMove::from_str("ab12"));
+    }
+
+    #[test]
+    fn err_on_invalid_coordinates() {
+        assert_eq!(
+            Err(ParseError::expected("a column letter", "12")),
+            BestMove::from_str("12")
+        );
+        assert_eq!(
+            Err(ParseError::expected("a number", "").shift(2)),
+            BestMove::from_str("ab")
+        );
+        assert_eq!(
+            Err(ParseError::invalid("rows are numbered from 1", "0").shift(1)),
+            BestMove::from_str("a0")
+        );
+        assert_eq!(
+            Err(ParseError::invalid("column is too large", "zzzzzzzz1")),
+            BestMove::from_str("zzzzzzzz1")
+        );
+    }
+
+    #[test]
+    fn multi_move_round_trips() {
+        let multi_move = MultiMove::new(vec![BestMove::new(0, 0), BestMove::new(2, 27)]);
+
+        assert_eq!(multi_move.to_string(), "best a1 ab3");
+        assert_eq!("best a1 ab3".parse::<MultiMove>(), Ok(multi_move));
+    }
+
+    #[test]
+    fn single_stone_multi_move_reads_like_best_move() {
+        let best_move = BestMove::new(4, 2);
+
+        assert_eq!(
+            MultiMove::from(best_move.clone()).to_string(),
+            best_move.to_string()
+        );
+    }
+
+    #[test]
+    fn multi_move_needs_a_coordinate() {
+        assert_eq!(
+            "best".parse::<MultiMove>(),
+            Err(ParseError::expected(" ", "").shift(4))
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use super::{ParseResult, Parser};
+use super::{ParseError, ParseResult, Parser};
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Nothing;
@@ -6,10 +6,10 @@ pub struct Nothing;
 pub struct NothingParser;
 
 impl Parser<Nothing> for NothingParser {
-    fn parse_from(val: &String) -> ParseResult<Nothing> {
+    fn parse_from(val: &str) -> ParseResult<'_, Nothing> {
         if val.is_empty() {
-            return Ok((Nothing {}, String::from("")));
+            return Ok((Nothing {}, val));
         }
-        Err(format!("Expected nothing, found string : {}", val))
+        Err(ParseError::expected("end of input", val))
     }
 }
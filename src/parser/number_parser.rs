@@ -1,13 +1,13 @@
 use std::fmt::Display;
 
-use super::{Digit, ParseResult, Parser};
+use super::{Digit, ParseError, ParseResult, Parser};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Number(pub u32);
 
-impl TryFrom<&String> for Number {
+impl TryFrom<&str> for Number {
     type Error = String;
-    fn try_from(value: &String) -> Result<Self, Self::Error> {
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         value
             .chars()
             .map(|c| Digit::try_from(c))
@@ -23,17 +23,16 @@ impl Display for Number {
 }
 
 impl Parser<Number> for Number {
-    fn parse_from(val: &String) -> ParseResult<Number> {
-        let digits = val
-            .chars()
-            .take_while(|c| Digit::is_digit(c))
-            .collect::<String>();
+    fn parse_from(val: &str) -> ParseResult<'_, Number> {
+        let digits = &val[..val.find(|c| !Digit::is_digit(&c)).unwrap_or(val.len())];
 
         if digits.is_empty() {
-            return Err("No digits found".to_string());
+            return Err(ParseError::expected("a number", val));
         }
 
-        Number::try_from(&digits).map(|number| (number, val[digits.len()..].to_string()))
+        Number::try_from(digits)
+            .map(|number| (number, &val[digits.len()..]))
+            .map_err(|reason| ParseError::invalid(reason, val))
     }
 }
 
@@ -43,23 +42,23 @@ mod test_number_try_from {
 
     #[test]
     fn should_read_a_valid_number_string() {
-        let number_string = "1234".to_string();
-        assert_eq!(Ok(Number(1234)), Number::try_from(&number_string));
+        let number_string = "1234";
+        assert_eq!(Ok(Number(1234)), Number::try_from(number_string));
     }
 
     #[test]
     fn should_not_read_an_invalid_number_string() {
-        let number_string = "1234-x".to_string();
+        let number_string = "1234-x";
         assert_eq!(
             Err(String::from("- is not a digit")),
-            Number::try_from(&number_string)
+            Number::try_from(number_string)
         );
     }
 
     #[test]
     fn should_read_a_valid_single_digit_string() {
-        let number_string = "1".to_string();
-        assert_eq!(Ok(Number(1)), Number::try_from(&number_string));
+        let number_string = "1";
+        assert_eq!(Ok(Number(1)), Number::try_from(number_string));
     }
 }
 
@@ -69,19 +68,16 @@ mod test_number_parser {
 
     #[test]
     fn should_parse_number_from_string() {
-        let number_string = "1234-x".to_string();
-        assert_eq!(
-            Ok((Number(1234), "-x".to_string())),
-            Number::parse_from(&number_string)
-        );
+        let number_string = "1234-x";
+        assert_eq!(Ok((Number(1234), "-x")), Number::parse_from(number_string));
     }
 
     #[test]
     fn err_if_string_has_no_number() {
-        let invalid_string = "xyz-".to_string();
+        let invalid_string = "xyz-";
         assert_eq!(
-            Err("No digits found".to_string()),
-            Number::parse_from(&invalid_string)
+            Err(ParseError::expected("a number", invalid_string)),
+            Number::parse_from(invalid_string)
         );
     }
 }
@@ -8,74 +8,80 @@ where
     P1: Parser<U>,
     P2: Parser<V>,
 {
-    fn parse_from(val: &String) -> ParseResult<Either<U, V>> {
+    fn parse_from(val: &str) -> ParseResult<'_, Either<U, V>> {
         P1::parse_from(val)
             .map(|(val, follow)| (Either::Left(val), follow))
-            .or_else(|_| P2::parse_from(val).map(|(val, follow)| (Either::Right(val), follow)))
+            .or_else(|left_error| {
+                P2::parse_from(val)
+                    .map(|(val, follow)| (Either::Right(val), follow))
+                    .map_err(|right_error| left_error.merge(right_error))
+            })
     }
 }
 
 pub type Or2<U, V> = OrParser<U, V>;
 pub type Or3<U, V, W> = Or2<U, Or2<V, W>>;
 pub type Or4<U, V, W, X> = Or2<U, Or3<V, W, X>>;
+pub type Or5<U, V, W, X, Y> = Or2<U, Or4<V, W, X, Y>>;
+pub type Or6<U, V, W, X, Y, Z> = Or2<U, Or5<V, W, X, Y, Z>>;
 
 #[cfg(test)]
 mod test_token_parser {
-    use crate::parser::{or_parser::Either, ParseResult, Parser};
+    use crate::parser::{or_parser::Either, ParseError, ParseResult, Parser};
 
     use super::OrParser;
 
     struct DummyParser1;
 
-    impl Parser<String> for DummyParser1 {
-        fn parse_from(val: &String) -> ParseResult<String> {
+    impl Parser<&'static str> for DummyParser1 {
+        fn parse_from(val: &str) -> ParseResult<'_, &'static str> {
             if val.contains("dummy1") {
-                return Ok(("dummy1".to_string(), " data".to_string()));
+                return Ok(("dummy1", " data"));
             }
 
-            Err("Not a valid dummy1".to_string())
+            Err(ParseError::expected("dummy1", val))
         }
     }
 
     struct DummyParser2;
 
     impl Parser<u32> for DummyParser2 {
-        fn parse_from(val: &String) -> ParseResult<u32> {
+        fn parse_from(val: &str) -> ParseResult<'_, u32> {
             if val.contains("dummy2") {
-                return Ok((2, "".to_string()));
+                return Ok((2, ""));
             }
 
-            Err("Not a valid dummy2".to_string())
+            Err(ParseError::expected("dummy2", val))
         }
     }
 
     #[test]
     fn test_or_parser_first_parser_succeeds() {
-        let input = String::from("dummy1 data");
+        let input = "dummy1 data";
 
-        let result = OrParser::<DummyParser1, DummyParser2>::parse_from(&input);
+        let result = OrParser::<DummyParser1, DummyParser2>::parse_from(input);
 
-        assert_eq!(
-            result,
-            Ok((Either::Left("dummy1".to_string()), " data".to_string()))
-        );
+        assert_eq!(result, Ok((Either::Left("dummy1"), " data")));
     }
 
     #[test]
     fn test_or_parser_second_parser_succeeds() {
-        let input = String::from("dummy2 data");
+        let input = "dummy2 data";
 
-        let result = OrParser::<DummyParser1, DummyParser2>::parse_from(&input);
+        let result = OrParser::<DummyParser1, DummyParser2>::parse_from(input);
 
-        assert_eq!(result, Ok((Either::Right(2), "".to_string())));
+        assert_eq!(result, Ok((Either::Right(2), "")));
     }
 
     #[test]
     fn err_if_both_parsers_fail() {
-        let input = String::from("dummy3 data");
+        let input = "dummy3 data";
 
-        let result = OrParser::<DummyParser1, DummyParser2>::parse_from(&input);
+        let result = OrParser::<DummyParser1, DummyParser2>::parse_from(input);
 
-        assert_eq!(result, Err("Not a valid dummy2".to_string()));
+        assert_eq!(
+            result,
+            Err(ParseError::expected_one_of(&["dummy1", "dummy2"], input))
+        );
     }
 }
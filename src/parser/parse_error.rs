@@ -0,0 +1,195 @@
+use std::fmt::Display;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ParseError {
+    /// None of the `expected` tokens were found at `offset`.
+    Expected {
+        offset: usize,
+        expected: Vec<String>,
+        found: String,
+    },
+    /// Something was found at `offset`, but it is not a valid value.
+    Invalid {
+        offset: usize,
+        reason: String,
+        found: String,
+    },
+}
+
+impl ParseError {
+    pub fn expected(expected: &str, val: &str) -> Self {
+        Self::expected_one_of(&[expected], val)
+    }
+
+    pub fn expected_one_of(expected: &[&str], val: &str) -> Self {
+        ParseError::Expected {
+            offset: 0,
+            expected: expected.iter().map(|e| e.to_string()).collect(),
+            found: fragment(val),
+        }
+    }
+
+    pub fn invalid(reason: impl Into<String>, val: &str) -> Self {
+        ParseError::Invalid {
+            offset: 0,
+            reason: reason.into(),
+            found: fragment(val),
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseError::Expected { offset, .. } | ParseError::Invalid { offset, .. } => *offset,
+        }
+    }
+
+    /// Moves the error `by` bytes to the right, used when the failing parser
+    /// only saw the tail of the input.
+    pub fn shift(mut self, by: usize) -> Self {
+        match &mut self {
+            ParseError::Expected { offset, .. } | ParseError::Invalid { offset, .. } => {
+                *offset += by
+            }
+        }
+        self
+    }
+
+    /// Combines the errors of two alternatives that were tried on the same input.
+    /// The alternative that got further wins; when both failed at the same place,
+    /// their expectations are joined.
+    pub fn merge(self, other: Self) -> Self {
+        if self.offset() != other.offset() {
+            return if self.offset() > other.offset() {
+                self
+            } else {
+                other
+            };
+        }
+
+        match (self, other) {
+            (
+                ParseError::Expected {
+                    offset,
+                    mut expected,
+                    found,
+                },
+                ParseError::Expected {
+                    expected: other_expected,
+                    ..
+                },
+            ) => {
+                for token in other_expected {
+                    if !expected.contains(&token) {
+                        expected.push(token);
+                    }
+                }
+                ParseError::Expected {
+                    offset,
+                    expected,
+                    found,
+                }
+            }
+            (invalid @ ParseError::Invalid { .. }, _) => invalid,
+            (_, invalid) => invalid,
+        }
+    }
+}
+
+fn fragment(val: &str) -> String {
+    val.split_whitespace().next().unwrap_or("").to_string()
+}
+
+fn describe_found(found: &str) -> String {
+    if found.is_empty() {
+        "end of input".to_string()
+    } else {
+        format!("\"{}\"", found)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Expected {
+                offset,
+                expected,
+                found,
+            } => {
+                let tokens = expected
+                    .iter()
+                    .map(|token| format!("\"{}\"", token))
+                    .collect::<Vec<String>>();
+                let expectation = match tokens.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        format!("{} or {}", rest.join(", "), last)
+                    }
+                    _ => tokens.join(""),
+                };
+                write!(
+                    f,
+                    "expected {} at offset {}, found {}",
+                    expectation,
+                    offset,
+                    describe_found(found)
+                )
+            }
+            ParseError::Invalid {
+                offset,
+                reason,
+                found,
+            } => write!(
+                f,
+                "{} at offset {}, found {}",
+                reason,
+                offset,
+                describe_found(found)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_parse_error {
+    use super::ParseError;
+
+    #[test]
+    fn keeps_the_error_that_got_further() {
+        let near = ParseError::expected("time", "tme ms:1");
+        let far = ParseError::expected("win-length", "3").shift(4);
+
+        assert_eq!(far.clone(), near.clone().merge(far.clone()));
+        assert_eq!(far.clone(), far.merge(near));
+    }
+
+    #[test]
+    fn joins_expectations_at_the_same_offset() {
+        let time = ParseError::expected("time", "tme ms:1");
+        let win_length = ParseError::expected("win-length", "tme ms:1");
+
+        assert_eq!(
+            ParseError::Expected {
+                offset: 0,
+                expected: vec!["time".to_string(), "win-length".to_string()],
+                found: "tme".to_string(),
+            },
+            time.merge(win_length)
+        );
+    }
+
+    #[test]
+    fn prefers_invalid_values_over_missing_tokens() {
+        let missing = ParseError::expected("ms", "1000");
+        let invalid = ParseError::invalid("1x is not a number", "1x");
+
+        assert_eq!(invalid.clone(), missing.merge(invalid));
+    }
+
+    #[test]
+    fn describes_expectations() {
+        let error = ParseError::expected_one_of(&["x", "o"], "").shift(9);
+        assert_eq!(
+            "expected \"x\" or \"o\" at offset 9, found end of input",
+            error.to_string()
+        );
+    }
+}
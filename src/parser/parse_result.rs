@@ -1 +1,3 @@
-pub type ParseResult<T> = Result<(T, String), String>;
+use super::ParseError;
+
+pub type ParseResult<'a, T> = Result<(T, &'a str), ParseError>;
@@ -1,4 +1,4 @@
 use super::ParseResult;
 pub trait Parser<T> {
-    fn parse_from(val: &String) -> ParseResult<T>;
+    fn parse_from(val: &str) -> ParseResult<'_, T>;
 }
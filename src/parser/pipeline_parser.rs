@@ -0,0 +1,243 @@
+use std::str::FromStr;
+
+use either::Either;
+
+use crate::{evaluator::Pipeline, generate_token_parser};
+
+use super::{
+    and_parser::{And2, And3},
+    nothing_parser::NothingParser,
+    or_parser::{Or2, Or4, Or5},
+    ParseError, ParseResult, Parser,
+};
+
+pub struct RowTokenParser;
+pub const ROW: &str = "row";
+generate_token_parser!(ROW, RowTokenParser);
+
+pub struct ColumnTokenParser;
+pub const COLUMN: &str = "column";
+generate_token_parser!(COLUMN, ColumnTokenParser);
+
+pub struct DiagonalTokenParser;
+pub const DIAGONAL: &str = "diagonal";
+generate_token_parser!(DIAGONAL, DiagonalTokenParser);
+
+pub struct LayerTokenParser;
+pub const LAYER: &str = "layer";
+generate_token_parser!(LAYER, LayerTokenParser);
+
+pub struct WinningTokenParser;
+pub const WINNING: &str = "winning";
+generate_token_parser!(WINNING, WinningTokenParser);
+
+pub struct OpponentTokenParser;
+pub const OPPONENT: &str = "opponent";
+generate_token_parser!(OPPONENT, OpponentTokenParser);
+
+pub struct MaxTokenParser;
+pub const MAX: &str = "max";
+generate_token_parser!(MAX, MaxTokenParser);
+
+pub struct SumTokenParser;
+pub const SUM: &str = "sum";
+generate_token_parser!(SUM, SumTokenParser);
+
+pub struct ScaleTokenParser;
+pub const SCALE: &str = "scale";
+generate_token_parser!(SCALE, ScaleTokenParser);
+
+pub struct OpenTokenParser;
+generate_token_parser!("(", OpenTokenParser);
+
+pub struct CloseTokenParser;
+generate_token_parser!(")", CloseTokenParser);
+
+pub struct CommaTokenParser;
+generate_token_parser!(",", CommaTokenParser);
+
+/// Skips whitespace, newlines and comments, which run from `#` to the end of the line.
+pub struct SpacingParser;
+
+impl Parser<()> for SpacingParser {
+    fn parse_from(val: &str) -> ParseResult<'_, ()> {
+        let mut rest = val.trim_start();
+        while let Some(comment) = rest.strip_prefix('#') {
+            rest = comment
+                .find('\n')
+                .map_or("", |end| &comment[end..])
+                .trim_start();
+        }
+        Ok(((), rest))
+    }
+}
+
+/// A decimal number, e.g. `2`, `1.5` or `-0.25`.
+pub struct DecimalParser;
+
+impl Parser<f32> for DecimalParser {
+    fn parse_from(val: &str) -> ParseResult<'_, f32> {
+        let length = val
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+            .unwrap_or(val.len());
+        if length == 0 {
+            return Err(ParseError::expected("a number", val));
+        }
+        let number = &val[..length];
+        number
+            .parse::<f32>()
+            .map(|number| (number, &val[length..]))
+            .map_err(|_| ParseError::invalid(format!("{} is not a number", number), val))
+    }
+}
+
+type OpenParser = And2<SpacingParser, OpenTokenParser>;
+type CloseParser = And2<SpacingParser, CloseTokenParser>;
+type CommaParser = And2<SpacingParser, CommaTokenParser>;
+type WeightParser = And3<CommaParser, And2<SpacingParser, DecimalParser>, CloseParser>;
+
+pub type LineParser = Or4<RowTokenParser, ColumnTokenParser, DiagonalTokenParser, LayerTokenParser>;
+pub type WinningParser =
+    And3<WinningTokenParser, OpenParser, And2<PipelineParser, Or2<WeightParser, CloseParser>>>;
+pub type OpponentParser = And3<OpponentTokenParser, OpenParser, And2<PipelineParser, CloseParser>>;
+pub type MaxParser = And3<MaxTokenParser, OpenParser, And2<PipelinesParser, CloseParser>>;
+pub type SumParser = And3<SumTokenParser, OpenParser, And2<PipelinesParser, CloseParser>>;
+pub type ScaleParser = And3<ScaleTokenParser, OpenParser, And2<PipelineParser, WeightParser>>;
+pub type CallParser = Or5<WinningParser, OpponentParser, MaxParser, SumParser, ScaleParser>;
+
+/// Reads one evaluator expression, see [`Pipeline`].
+pub struct PipelineParser;
+
+impl Parser<Pipeline> for PipelineParser {
+    fn parse_from(val: &str) -> ParseResult<'_, Pipeline> {
+        let ((_, output), rest) =
+            And2::<SpacingParser, Or2<LineParser, CallParser>>::parse_from(val)?;
+        let pipeline = match output {
+            Either::Left(line) => match line.either(
+                |line| line,
+                |line| line.either(|line| line, Either::into_inner),
+            ) {
+                ROW => Pipeline::Row,
+                COLUMN => Pipeline::Column,
+                DIAGONAL => Pipeline::Diagonal,
+                _ => Pipeline::Layer,
+            },
+            Either::Right(Either::Left((_, (_, (pipeline, weight))))) => {
+                let sharpness = match weight {
+                    Either::Left((_, ((_, sharpness), _))) => sharpness,
+                    Either::Right(_) => 1.0,
+                };
+                Pipeline::Winning(Box::new(pipeline), sharpness)
+            }
+            Either::Right(Either::Right(Either::Left((_, (_, (pipeline, _)))))) => {
+                Pipeline::Opponent(Box::new(pipeline))
+            }
+            Either::Right(Either::Right(Either::Right(Either::Left((_, (_, (pipelines, _))))))) => {
+                Pipeline::Max(pipelines)
+            }
+            Either::Right(Either::Right(Either::Right(Either::Right(Either::Left((
+                _,
+                (_, (pipelines, _)),
+            )))))) => Pipeline::Sum(pipelines),
+            Either::Right(Either::Right(Either::Right(Either::Right(Either::Right((
+                _,
+                (_, (pipeline, (_, ((_, weight), _)))),
+            )))))) => Pipeline::Scale(Box::new(pipeline), weight),
+        };
+        Ok((pipeline, rest))
+    }
+}
+
+/// Reads one or more evaluator expressions separated by commas.
+pub struct PipelinesParser;
+
+impl Parser<Vec<Pipeline>> for PipelinesParser {
+    fn parse_from(val: &str) -> ParseResult<'_, Vec<Pipeline>> {
+        let (first, mut rest) = PipelineParser::parse_from(val)?;
+        let mut pipelines = vec![first];
+        while CommaParser::parse_from(rest).is_ok() {
+            let ((_, pipeline), follow) = And2::<CommaParser, PipelineParser>::parse_from(rest)
+                .map_err(|error| error.shift(val.len() - rest.len()))?;
+            pipelines.push(pipeline);
+            rest = follow;
+        }
+        Ok((pipelines, rest))
+    }
+}
+
+impl FromStr for Pipeline {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        And2::<PipelineParser, And2<SpacingParser, NothingParser>>::parse_from(s)
+            .map(|((pipeline, _), _)| pipeline)
+    }
+}
+
+#[cfg(test)]
+mod test_pipeline_parser {
+    use crate::{
+        evaluator::{Pipeline, Weights},
+        parser::ParseError,
+    };
+
+    #[test]
+    fn reads_nested_evaluators() {
+        let pipeline = "# Block harder\nmax(winning(row), scale(winning(opponent(row), 2), 1.5))"
+            .parse::<Pipeline>()
+            .unwrap();
+        assert_eq!(
+            pipeline,
+            Pipeline::Max(vec![
+                Pipeline::Winning(Box::new(Pipeline::Row), 1.0),
+                Pipeline::Scale(
+                    Box::new(Pipeline::Winning(
+                        Box::new(Pipeline::Opponent(Box::new(Pipeline::Row))),
+                        2.0
+                    )),
+                    1.5
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let pipeline = Pipeline::greedy(&Weights::default());
+        assert_eq!(pipeline.to_string().parse::<Pipeline>(), Ok(pipeline));
+    }
+
+    #[test]
+    fn points_at_what_it_cannot_read() {
+        assert_eq!(
+            "max(row, colum)".parse::<Pipeline>(),
+            Err(ParseError::expected_one_of(
+                &[
+                    "row", "column", "diagonal", "layer", "winning", "opponent", "max", "sum",
+                    "scale"
+                ],
+                "colum)"
+            )
+            .shift(9))
+        );
+        assert_eq!(
+            "scale(row)".parse::<Pipeline>(),
+            Err(ParseError::expected(",", ")").shift(9))
+        );
+        assert_eq!(
+            "scale(row, x)".parse::<Pipeline>(),
+            Err(ParseError::expected("a number", "x)").shift(11))
+        );
+        assert_eq!(
+            "row row".parse::<Pipeline>(),
+            Err(ParseError::expected("end of input", "row").shift(4))
+        );
+    }
+
+    #[test]
+    fn counts_offsets_in_the_text_as_written() {
+        let text = "# Only rows\nsum(row,\n    # and columns\n    colum)";
+        let error = text.parse::<Pipeline>().unwrap_err();
+        assert_eq!(&text[error.offset()..], "colum)");
+    }
+}
@@ -0,0 +1,375 @@
+use super::{
+    board::{Board, DIRECTIONS},
+    Cell, Player,
+};
+
+/// The kinds of moves Renju rules forbid X from making.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Forbidden {
+    Overline,
+    DoubleFour,
+    DoubleThree,
+}
+
+impl Board {
+    /// Why X may not play at (x, y) under Renju rules, if it may not. Making a five is
+    /// always allowed; otherwise an overline, two fours or two open threes are forbidden.
+    pub fn forbidden_move(&self, x: usize, y: usize, win_length: u32) -> Option<Forbidden> {
+        self.forbidden(x, y, win_length, false)
+    }
+
+    /// Like [`Board::forbidden_move`]. A three only counts as open if the point that makes
+    /// it a straight four is not forbidden itself; that point is checked `nested`, where
+    /// threes count as open without looking any further.
+    fn forbidden(&self, x: usize, y: usize, win_length: u32, nested: bool) -> Option<Forbidden> {
+        if self.get_cell(x as isize, y as isize) != Some(Cell::Playable)
+            || !self.may_be_forbidden(x, y, win_length)
+        {
+            return None;
+        }
+
+        let mut board = self.clone();
+        board.play_move(x as u32, y as u32, Player::X);
+
+        let runs = DIRECTIONS.map(|direction| board.run_through(x, y, direction, Player::X).length);
+        if runs.contains(&win_length) {
+            return None;
+        }
+        if runs.iter().any(|&length| length > win_length) {
+            return Some(Forbidden::Overline);
+        }
+
+        let fours = DIRECTIONS.map(|direction| board.fours_through(x, y, direction, win_length));
+        if fours.iter().sum::<usize>() >= 2 {
+            return Some(Forbidden::DoubleFour);
+        }
+
+        let open_threes = DIRECTIONS
+            .iter()
+            .zip(fours)
+            .filter(|&(&direction, fours)| {
+                fours == 0 && board.is_open_three(x, y, direction, win_length, nested)
+            })
+            .count();
+        if open_threes >= 2 {
+            return Some(Forbidden::DoubleThree);
+        }
+
+        None
+    }
+
+    /// Whether there are enough X stones in reach of (x, y) for a forbidden shape: an
+    /// overline or two fours along one line, or a three or four along two of them. Most
+    /// points fail this, and are not worth placing a stone on to look closer.
+    fn may_be_forbidden(&self, x: usize, y: usize, win_length: u32) -> bool {
+        let reach = win_length as isize - 1;
+        let stones = DIRECTIONS.map(|direction| {
+            (-reach..=reach)
+                .filter(|&i| {
+                    i != 0 && self.cell_along(x, y, direction, i) == Some(Cell::Played(Player::X))
+                })
+                .count()
+        });
+        let threes = stones
+            .iter()
+            .filter(|&&count| count >= win_length.saturating_sub(3) as usize)
+            .count();
+        threes >= 2 || stones.iter().any(|&count| count >= reach as usize)
+    }
+
+    /// Counts the distinct fours of X through (x, y) along `direction`: sets of stones that
+    /// one more stone turns into exactly a five. A straight four counts once.
+    fn fours_through(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+        win_length: u32,
+    ) -> usize {
+        let stone = Some(Cell::Played(Player::X));
+        let cell = |i: isize| self.cell_along(x, y, direction, i);
+        let length = win_length as isize;
+
+        let mut fours: Vec<Vec<isize>> = Vec::new();
+        for start in (1 - length)..=0 {
+            let window = start..start + length;
+            let gaps: Vec<isize> = window.clone().filter(|&i| cell(i) != stone).collect();
+            if gaps.len() != 1
+                || cell(gaps[0]) != Some(Cell::Playable)
+                || cell(start - 1) == stone
+                || cell(start + length) == stone
+            {
+                continue;
+            }
+
+            let stones: Vec<isize> = window.filter(|&i| cell(i) == stone).collect();
+            if !fours.contains(&stones) {
+                fours.push(stones);
+            }
+        }
+        fours.len()
+    }
+
+    /// Whether X has an open three through (x, y) along `direction`: one more stone, on a
+    /// point X is allowed to play, makes a straight four. `nested` skips checking that point.
+    fn is_open_three(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+        win_length: u32,
+        nested: bool,
+    ) -> bool {
+        let reach = win_length as isize - 1;
+        (-reach..=reach).any(|i| {
+            let Some((ex, ey)) = self.position_along(x, y, direction, i) else {
+                return false;
+            };
+            if i == 0 || self.cell_along(x, y, direction, i) != Some(Cell::Playable) {
+                return false;
+            }
+
+            let mut board = self.clone();
+            board.play_move(ex as u32, ey as u32, Player::X);
+            board.is_straight_four(x, y, direction, win_length)
+                && (nested || self.forbidden(ex, ey, win_length, true).is_none())
+        })
+    }
+
+    /// Whether X has a run of one short of a five through (x, y) along `direction` that
+    /// becomes exactly a five at either end.
+    fn is_straight_four(
+        &self,
+        x: usize,
+        y: usize,
+        direction: (isize, isize),
+        win_length: u32,
+    ) -> bool {
+        let stone = Some(Cell::Played(Player::X));
+        let (dx, dy) = direction;
+        let cell = |i: isize| self.cell_along(x, y, direction, i);
+
+        let run = self.run_through(x, y, direction, Player::X);
+        if run.before.is_none() && run.after.is_none() {
+            return false;
+        }
+        let after = 1 + self
+            .walk(x, y, direction)
+            .take_while(|&c| Some(c) == stone)
+            .count() as isize;
+        let before = -1
+            - self
+                .walk(x, y, (-dx, -dy))
+                .take_while(|&c| Some(c) == stone)
+                .count() as isize;
+
+        after - before - 1 == win_length as isize - 1
+            && cell(before) == Some(Cell::Playable)
+            && cell(after) == Some(Cell::Playable)
+            && cell(before - 1) != stone
+            && cell(after + 1) != stone
+    }
+}
+
+#[cfg(test)]
+mod test_renju {
+    use std::str::FromStr;
+
+    use crate::parser::{BestMove, Board, BoardState, Cell, Player, Rules, TimeSetting, WinRule};
+
+    use super::Forbidden;
+
+    fn diagram(rows: &[&str]) -> Board {
+        Board::new(
+            rows.iter()
+                .map(|row| {
+                    row.chars()
+                        .map(|c| Cell::from_str(&c.to_string()).unwrap())
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    fn renju(board: Board, player: Player) -> BoardState {
+        BoardState::new(player, board, TimeSetting::Infinite, Some(5)).with_rules(Rules {
+            renju: true,
+            x_win_rule: WinRule::ExactLength,
+            ..Rules::default()
+        })
+    }
+
+    #[test]
+    fn double_three_is_forbidden() {
+        let board = diagram(&[
+            "_________",
+            "_________",
+            "____x____",
+            "____x____",
+            "__xx_____",
+            "_________",
+            "_________",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 4, 5), Some(Forbidden::DoubleThree));
+    }
+
+    #[test]
+    fn split_three_counts_as_open() {
+        let board = diagram(&[
+            "_________",
+            "_________",
+            "____x____",
+            "_________",
+            "__xx_____",
+            "____x____",
+            "_________",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 4, 5), Some(Forbidden::DoubleThree));
+    }
+
+    #[test]
+    fn blocked_three_is_not_open() {
+        let board = diagram(&[
+            "_________",
+            "_________",
+            "____x____",
+            "____x____",
+            "_oxx_____",
+            "_________",
+            "_________",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 4, 5), None);
+    }
+
+    #[test]
+    fn three_without_room_for_a_straight_four_is_not_open() {
+        let board = diagram(&[
+            "_________",
+            "_________",
+            "____x____",
+            "____x____",
+            "o_xx__o__",
+            "_________",
+            "_________",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 4, 5), None);
+    }
+
+    #[test]
+    fn double_four_is_forbidden() {
+        let board = diagram(&[
+            "_________",
+            "____x____",
+            "____x____",
+            "____x____",
+            "_xxx_____",
+            "_________",
+            "_________",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 4, 5), Some(Forbidden::DoubleFour));
+    }
+
+    #[test]
+    fn double_four_in_one_line_is_forbidden() {
+        let board = diagram(&["xxx___xxx", "_________", "_________"]);
+        assert_eq!(board.forbidden_move(0, 4, 5), Some(Forbidden::DoubleFour));
+        assert_eq!(board.forbidden_move(0, 3, 5), None);
+    }
+
+    #[test]
+    fn straight_four_counts_as_one_four() {
+        let board = diagram(&["_xxx_____", "_________", "_________"]);
+        assert_eq!(board.forbidden_move(0, 4, 5), None);
+    }
+
+    #[test]
+    fn four_three_is_allowed() {
+        let board = diagram(&[
+            "_________",
+            "_________",
+            "____x____",
+            "____x____",
+            "_xxx_____",
+            "_________",
+            "_________",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 4, 5), None);
+    }
+
+    #[test]
+    fn overline_is_forbidden() {
+        let board = diagram(&["xxx_xx___", "_________", "_________"]);
+        assert_eq!(board.forbidden_move(0, 3, 5), Some(Forbidden::Overline));
+    }
+
+    #[test]
+    fn five_wins_over_a_forbidden_shape() {
+        let board = diagram(&[
+            "____x____",
+            "____x____",
+            "____x____",
+            "_________",
+            "xxxx_____",
+            "_________",
+            "_________",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 4, 5), None);
+    }
+
+    #[test]
+    fn three_whose_four_point_is_forbidden_is_not_open() {
+        // The row three through (4, 4) can only become a straight four at (4, 6), which
+        // would make an overline in its column, so only the column three is open.
+        let board = diagram(&[
+            "_________",
+            "______x__",
+            "____x_x__",
+            "____x_x__",
+            "_o_x_x___",
+            "______x__",
+            "______x__",
+            "_________",
+            "_________",
+        ]);
+        assert_eq!(board.forbidden_move(4, 6, 5), Some(Forbidden::Overline));
+        assert_eq!(board.forbidden_move(4, 4, 5), None);
+    }
+
+    #[test]
+    fn forbidden_points_are_not_offered_to_x() {
+        let board = diagram(&["xxx_xx", "______", "oo____", "______", "______", "______"]);
+
+        let board_state = renju(board.clone(), Player::X);
+        assert!(!board_state
+            .get_possible_moves()
+            .contains(&BestMove::new(0, 3)));
+
+        let board_state = renju(board, Player::O);
+        assert!(board_state
+            .get_possible_moves()
+            .contains(&BestMove::new(0, 3)));
+    }
+
+    #[test]
+    fn forbidden_points_are_dead_for_x() {
+        let board = diagram(&["xxx_xx", "______", "oo____", "______", "______", "______"]);
+        let board_state = renju(board, Player::X);
+
+        assert!(!board_state.can_win_along(0, 3, (1, 0), Player::X));
+        assert!(board_state.can_win_along(0, 3, (1, 0), Player::O));
+    }
+}
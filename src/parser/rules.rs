@@ -0,0 +1,376 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{
+    board::{Run, Topology},
+    Cell, Player,
+};
+
+/// Which runs of `win_length` or more stones count as a win.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum WinRule {
+    /// Any run of at least `win_length` stones wins.
+    #[default]
+    FreeStyle,
+    /// Only a run of exactly `win_length` stones wins, overlines do not count.
+    ExactLength,
+    /// A run of at least `win_length` stones wins unless the opponent blocks both of its ends.
+    Caro,
+}
+
+impl WinRule {
+    pub fn is_win(&self, run: &Run, win_length: u32, player: Player) -> bool {
+        match self {
+            WinRule::FreeStyle => run.length >= win_length,
+            WinRule::ExactLength => run.length == win_length,
+            WinRule::Caro => {
+                let blocker = Some(Cell::Played(player.opponent()));
+                run.length >= win_length && !(run.before == blocker && run.after == blocker)
+            }
+        }
+    }
+}
+
+impl FromStr for WinRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "free-style" => Ok(WinRule::FreeStyle),
+            "exact" => Ok(WinRule::ExactLength),
+            "caro" => Ok(WinRule::Caro),
+            _ => Err(format!("{} is not a valid win rule.", s)),
+        }
+    }
+}
+
+impl Display for WinRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WinRule::FreeStyle => write!(f, "free-style"),
+            WinRule::ExactLength => write!(f, "exact"),
+            WinRule::Caro => write!(f, "caro"),
+        }
+    }
+}
+
+/// How many stones a player places per turn: `first` on the opening turn of the game and
+/// `rest` on every turn after it, e.g. 1 then 2 for Connect6.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct StonesPerTurn {
+    pub first: u32,
+    pub rest: u32,
+}
+
+impl Default for StonesPerTurn {
+    fn default() -> Self {
+        Self { first: 1, rest: 1 }
+    }
+}
+
+impl FromStr for StonesPerTurn {
+    type Err = String;
+
+    /// Reads either `<first>,<rest>` or a single count used for every turn.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("{} is not a valid number of stones per turn.", s);
+        let count = |count: &str| match count.parse::<u32>() {
+            Ok(count) if count > 0 => Ok(count),
+            _ => Err(invalid()),
+        };
+
+        match s.split_once(',') {
+            Some((first, rest)) => Ok(Self {
+                first: count(first)?,
+                rest: count(rest)?,
+            }),
+            None => {
+                let count = count(s)?;
+                Ok(Self {
+                    first: count,
+                    rest: count,
+                })
+            }
+        }
+    }
+}
+
+impl Display for StonesPerTurn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.first == self.rest {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{},{}", self.first, self.rest)
+        }
+    }
+}
+
+/// Variant rules a game is played under, on top of the board and win length
+/// that come with every `move` command.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Rules {
+    /// Stones drop to the lowest playable cell of the column they are played in,
+    /// as in Connect Four.
+    pub gravity: bool,
+    /// X may not make double-threes, double-fours or overlines, and only wins with exactly
+    /// five in a row.
+    pub renju: bool,
+    /// Completing a line loses instead of winning.
+    pub misere: bool,
+    pub stones_per_turn: StonesPerTurn,
+    /// Which edges of the board lines wrap around.
+    pub topology: Topology,
+    pub x_win_rule: WinRule,
+    pub o_win_rule: WinRule,
+}
+
+impl Rules {
+    pub fn win_rule(&self, player: Player) -> WinRule {
+        match player {
+            Player::X => self.x_win_rule,
+            Player::O => self.o_win_rule,
+        }
+    }
+
+    /// Reads the variant rules from the engine's command line flags.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut rules = Rules::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--gravity" => rules.gravity = true,
+                "--misere" => rules.misere = true,
+                "--renju" => {
+                    rules.renju = true;
+                    rules.x_win_rule = WinRule::ExactLength;
+                }
+                "--win-rule" => {
+                    let rule = Self::value_of(&arg, args.next())?;
+                    rules.x_win_rule = rule;
+                    rules.o_win_rule = rule;
+                }
+                "--stones-per-turn" => rules.stones_per_turn = Self::value_of(&arg, args.next())?,
+                "--wrap" => rules.topology = Self::value_of(&arg, args.next())?,
+                "--x-win-rule" => rules.x_win_rule = Self::value_of(&arg, args.next())?,
+                "--o-win-rule" => rules.o_win_rule = Self::value_of(&arg, args.next())?,
+                unknown => return Err(format!("Unknown option: {}", unknown)),
+            }
+        }
+        Ok(rules)
+    }
+
+    fn value_of<T: FromStr<Err = String>>(flag: &str, value: Option<String>) -> Result<T, String> {
+        value
+            .ok_or_else(|| format!("Missing value for {}", flag))?
+            .parse::<T>()
+    }
+}
+
+/// Writes the command line flags [`Rules::from_args`] reads these rules from, leaving out
+/// every rule that is at its default.
+impl Display for Rules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut flags = Vec::new();
+        if self.gravity {
+            flags.push("--gravity".to_string());
+        }
+        if self.misere {
+            flags.push("--misere".to_string());
+        }
+        if self.renju {
+            flags.push("--renju".to_string());
+        }
+        if self.stones_per_turn != StonesPerTurn::default() {
+            flags.push(format!("--stones-per-turn {}", self.stones_per_turn));
+        }
+        if self.topology != Topology::default() {
+            flags.push(format!("--wrap {}", self.topology));
+        }
+
+        // --renju already makes X's win rule exact.
+        let x_default = if self.renju {
+            WinRule::ExactLength
+        } else {
+            WinRule::default()
+        };
+        if self.x_win_rule == self.o_win_rule && !self.renju {
+            if self.x_win_rule != WinRule::default() {
+                flags.push(format!("--win-rule {}", self.x_win_rule));
+            }
+        } else {
+            if self.x_win_rule != x_default {
+                flags.push(format!("--x-win-rule {}", self.x_win_rule));
+            }
+            if self.o_win_rule != WinRule::default() {
+                flags.push(format!("--o-win-rule {}", self.o_win_rule));
+            }
+        }
+        write!(f, "{}", flags.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::parser::{
+        board::{Run, Topology},
+        Cell, Player,
+    };
+
+    use super::{Rules, StonesPerTurn, WinRule};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn default_rules_without_args() {
+        assert_eq!(Ok(Rules::default()), Rules::from_args(vec![]));
+    }
+
+    #[test]
+    fn read_gravity_flag() {
+        let rules = Rules::from_args(args(&["--gravity"]));
+        assert_eq!(
+            Ok(Rules {
+                gravity: true,
+                ..Rules::default()
+            }),
+            rules
+        );
+    }
+
+    #[test]
+    fn read_win_rules_per_player() {
+        let rules = Rules::from_args(args(&["--win-rule", "exact", "--o-win-rule", "free-style"]));
+        assert_eq!(
+            Ok(Rules {
+                x_win_rule: WinRule::ExactLength,
+                o_win_rule: WinRule::FreeStyle,
+                ..Rules::default()
+            }),
+            rules
+        );
+    }
+
+    #[test]
+    fn read_renju_flag() {
+        let rules = Rules::from_args(args(&["--renju"]));
+        assert_eq!(
+            Ok(Rules {
+                renju: true,
+                x_win_rule: WinRule::ExactLength,
+                ..Rules::default()
+            }),
+            rules
+        );
+    }
+
+    #[test]
+    fn read_misere_flag() {
+        let rules = Rules::from_args(args(&["--misere", "--gravity"]));
+        assert_eq!(
+            Ok(Rules {
+                misere: true,
+                gravity: true,
+                ..Rules::default()
+            }),
+            rules
+        );
+    }
+
+    #[test]
+    fn read_stones_per_turn() {
+        let rules = Rules::from_args(args(&["--stones-per-turn", "1,2"]));
+        assert_eq!(
+            Ok(Rules {
+                stones_per_turn: StonesPerTurn { first: 1, rest: 2 },
+                ..Rules::default()
+            }),
+            rules
+        );
+        assert_eq!(Ok(StonesPerTurn { first: 3, rest: 3 }), "3".parse());
+        assert_eq!(
+            Err("1,0 is not a valid number of stones per turn.".to_string()),
+            "1,0".parse::<StonesPerTurn>()
+        );
+    }
+
+    #[test]
+    fn read_wrap() {
+        let rules = Rules::from_args(args(&["--wrap", "horizontal"]));
+        assert_eq!(
+            Ok(Rules {
+                topology: Topology {
+                    wrap_horizontal: true,
+                    wrap_vertical: false,
+                },
+                ..Rules::default()
+            }),
+            rules
+        );
+        assert_eq!(
+            Err("sideways is not a valid wrap.".to_string()),
+            Rules::from_args(args(&["--wrap", "sideways"]))
+        );
+    }
+
+    #[test]
+    fn write_the_flags_back() {
+        for flags in [
+            "",
+            "--gravity --misere",
+            "--renju --o-win-rule caro",
+            "--stones-per-turn 1,2 --wrap both",
+            "--win-rule exact",
+            "--x-win-rule caro",
+        ] {
+            let rules = Rules::from_args(flags.split_whitespace().map(str::to_string)).unwrap();
+            assert_eq!(flags, rules.to_string());
+        }
+    }
+
+    #[test]
+    fn err_on_unknown_flag() {
+        let rules = Rules::from_args(args(&["--levitation"]));
+        assert_eq!(Err("Unknown option: --levitation".to_string()), rules);
+    }
+
+    #[test]
+    fn err_on_missing_or_invalid_value() {
+        assert_eq!(
+            Err("Missing value for --win-rule".to_string()),
+            Rules::from_args(args(&["--win-rule"]))
+        );
+        assert_eq!(
+            Err("renju is not a valid win rule.".to_string()),
+            Rules::from_args(args(&["--x-win-rule", "renju"]))
+        );
+    }
+
+    #[test]
+    fn overlines_only_win_free_style() {
+        let overline = Run {
+            length: 6,
+            before: None,
+            after: Some(Cell::Playable),
+        };
+
+        assert!(WinRule::FreeStyle.is_win(&overline, 5, Player::X));
+        assert!(!WinRule::ExactLength.is_win(&overline, 5, Player::X));
+    }
+
+    #[test]
+    fn caro_needs_an_open_end() {
+        let blocked = Run {
+            length: 5,
+            before: Some(Cell::Played(Player::O)),
+            after: Some(Cell::Played(Player::O)),
+        };
+        let half_open = Run {
+            after: None,
+            ..blocked
+        };
+
+        assert!(!WinRule::Caro.is_win(&blocked, 5, Player::X));
+        assert!(WinRule::Caro.is_win(&half_open, 5, Player::X));
+    }
+}
@@ -7,7 +7,6 @@ use super::{
     number_parser::Number,
     version_parser::{Version, VersionParser},
     whitespace_parser::WhiteSpaceParser,
-    ParseResult, Parser,
 };
 
 #[derive(PartialEq, Eq, Debug)]
@@ -32,7 +31,10 @@ pub const ST3P: &str = "st3p";
 generate_token_parser!(ST3P, StepTokenParser);
 
 pub type StepParser = And3<StepTokenParser, WhiteSpaceParser, VersionParser>;
-pub type StepParserReturnType = (String, (String, (String, (String, Number))));
+pub type StepParserReturnType = (
+    &'static str,
+    (&'static str, (&'static str, (&'static str, Number))),
+);
 
 #[cfg(test)]
 mod test_step_parser {
@@ -44,19 +46,10 @@ mod test_step_parser {
 
     #[test]
     fn parse_step() {
-        let version_string = "st3p version 1".to_string();
-        let res = StepParser::parse_from(&version_string);
+        let version_string = "st3p version 1";
+        let res = StepParser::parse_from(version_string);
         assert_eq!(
-            (Ok((
-                (
-                    ST3P.to_string(),
-                    (
-                        " ".to_string(),
-                        ("version".to_string(), (" ".to_string(), Number(1)))
-                    )
-                ),
-                "".to_string()
-            ))),
+            (Ok(((ST3P, (" ", ("version", (" ", Number(1))))), ""))),
             res
         );
     }
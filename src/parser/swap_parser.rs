@@ -0,0 +1,109 @@
+use either::Either;
+
+use crate::generate_token_parser;
+
+use super::{
+    and_parser::{And2, And3},
+    move_parser::{BoardState, MoveOptions, MoveOptionsParser, T3NParser},
+    or_parser::Or2,
+    whitespace_parser::WhiteSpaceParser,
+    Board, Player,
+};
+
+pub struct SwapTokenParser;
+
+pub const SWAP: &str = "swap";
+
+generate_token_parser!(SWAP, SwapTokenParser);
+
+pub struct Swap2TokenParser;
+
+pub const SWAP2: &str = "swap2";
+
+generate_token_parser!(SWAP2, Swap2TokenParser);
+
+/// The opening protocols in which a player may take over the other side.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SwapRule {
+    /// The pie rule: once the opening is proposed, the other player either swaps sides or
+    /// plays on.
+    Pie,
+    /// Swap2: the first player places three stones, the second either swaps, plays on or
+    /// places two more and lets the first player pick a side.
+    Swap2,
+}
+
+/// `swap2` has to be tried first, as `swap` is a prefix of it.
+pub type SwapRuleParser = Or2<Swap2TokenParser, SwapTokenParser>;
+
+/// Parses `swap <t3n> <player> [options]` and `swap2 <t3n> <player> [options]`, which
+/// take the same arguments as `move`.
+pub type SwapParser = And2<And3<SwapRuleParser, WhiteSpaceParser, T3NParser>, MoveOptionsParser>;
+pub type SwapParserReturnType = (
+    (
+        Either<&'static str, &'static str>,
+        (&'static str, (Board, Player)),
+    ),
+    MoveOptions,
+);
+
+pub fn from_swap_parser(value: SwapParserReturnType) -> (SwapRule, BoardState) {
+    let ((rule, arguments), options) = value;
+    let (rule, token) = match rule {
+        Either::Left(token) => (SwapRule::Swap2, token),
+        Either::Right(token) => (SwapRule::Pie, token),
+    };
+    (rule, BoardState::from(((token, arguments), options)))
+}
+
+#[cfg(test)]
+mod test_swap_parser {
+    use crate::parser::{
+        board::Board,
+        move_parser::{BoardState, Player},
+        time_parser::TimeSetting,
+        ParseError, Parser,
+    };
+
+    use super::{from_swap_parser, SwapParser, SwapRule};
+
+    #[test]
+    fn parses_the_pie_rule() {
+        let res = SwapParser::parse_from("swap 3_/_x_/3_ o win-length 3")
+            .map(|(value, _)| from_swap_parser(value));
+        assert_eq!(
+            res,
+            Ok((
+                SwapRule::Pie,
+                BoardState::new(
+                    Player::O,
+                    "3_/_x_/3_".parse::<Board>().unwrap(),
+                    TimeSetting::Infinite,
+                    Some(3)
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_swap2() {
+        let res =
+            SwapParser::parse_from("swap2 15_/15_/15_ x").map(|(value, _)| from_swap_parser(value));
+        assert_eq!(
+            res.map(|(rule, board_state)| (rule, board_state.player_to_move)),
+            Ok((SwapRule::Swap2, Player::X))
+        );
+    }
+
+    #[test]
+    fn reports_both_protocols_for_unknown_input() {
+        let res = SwapParser::parse_from("swop 3_/3_/3_ x");
+        assert_eq!(
+            res.map(|(value, _)| from_swap_parser(value)),
+            Err(ParseError::expected_one_of(
+                &["swap2", "swap"],
+                "swop 3_/3_/3_ x"
+            ))
+        );
+    }
+}
@@ -1,8 +1,17 @@
+use std::{fmt::Display, str::FromStr};
+
+use either::Either;
+
 use crate::generate_token_parser;
 
 use super::{
-    and_parser::And3, number_parser::Number, whitespace_parser::WhiteSpaceParser, ParseResult,
-    Parser,
+    and_parser::{And2, And3},
+    move_parser::{MoveOptions, MoveOptionsParser},
+    nothing_parser::NothingParser,
+    number_parser::Number,
+    or_parser::Or3,
+    whitespace_parser::WhiteSpaceParser,
+    ParseError, ParseResult, Parser,
 };
 
 const TIME: &str = "time";
@@ -13,47 +22,262 @@ pub struct TimeRemainingTokenParser;
 const TIME_REMAINING: &str = "time-remaining";
 generate_token_parser!(TIME_REMAINING, TimeRemainingTokenParser);
 
+pub struct IncrementTokenParser;
+const INCREMENT: &str = "increment";
+generate_token_parser!(INCREMENT, IncrementTokenParser);
+
+pub struct MovesToGoTokenParser;
+const MOVES_TO_GO: &str = "moves-to-go";
+generate_token_parser!(MOVES_TO_GO, MovesToGoTokenParser);
+
+/// Number of moves a clock is expected to last for when the controller does not say.
+const DEFAULT_MOVES_TO_GO: u32 = 20;
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum TimeSetting {
     TotalTime(Number),
     TimeRemaining(Number),
     Infinite,
+    /// A game clock with `remaining` milliseconds on it, `increment` milliseconds added
+    /// after every move and optionally `moves_to_go` moves until the next time control.
+    Clock {
+        remaining: Number,
+        increment: Number,
+        moves_to_go: Option<Number>,
+    },
 }
 
-pub struct TimeParser;
+impl TimeSetting {
+    /// Milliseconds that can be spent on the current move, `None` when there is no limit.
+    pub fn allocate(&self) -> Option<Number> {
+        match self {
+            TimeSetting::TotalTime(time) => Some(time.clone()),
+            TimeSetting::TimeRemaining(remaining) => {
+                Some(Number(remaining.0 / DEFAULT_MOVES_TO_GO))
+            }
+            TimeSetting::Infinite => None,
+            TimeSetting::Clock {
+                remaining,
+                increment,
+                moves_to_go,
+            } => {
+                let moves_to_go = moves_to_go
+                    .as_ref()
+                    .map_or(DEFAULT_MOVES_TO_GO, |moves| moves.0.max(1));
+                let budget = (remaining.0 / moves_to_go).saturating_add(increment.0);
+                Some(Number(budget.min(remaining.0)))
+            }
+        }
+    }
 
+    /// Milliseconds the current move may take at most before the clock runs out, `None`
+    /// when there is no limit.
+    pub fn limit(&self) -> Option<Number> {
+        match self {
+            TimeSetting::TotalTime(time) | TimeSetting::TimeRemaining(time) => Some(time.clone()),
+            TimeSetting::Infinite => None,
+            TimeSetting::Clock { remaining, .. } => Some(remaining.clone()),
+        }
+    }
+}
+
+impl Display for TimeSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeSetting::TotalTime(time) => write!(f, "{} {}:{}", TIME, MILLIS, time),
+            TimeSetting::TimeRemaining(time) => write!(f, "{} {}:{}", TIME_REMAINING, MILLIS, time),
+            TimeSetting::Infinite => Ok(()),
+            TimeSetting::Clock {
+                remaining,
+                increment,
+                moves_to_go,
+            } => {
+                write!(
+                    f,
+                    "{} {}:{} {} {}:{}",
+                    TIME_REMAINING, MILLIS, remaining, INCREMENT, MILLIS, increment
+                )?;
+                if let Some(moves) = moves_to_go {
+                    write!(f, " {} {}", MOVES_TO_GO, moves)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Reads a time setting written as in a `move` command, e.g. `time-remaining s:60 increment
+/// s:1`, and no time setting at all as [`TimeSetting::Infinite`].
+impl FromStr for TimeSetting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(TimeSetting::Infinite);
+        }
+        let invalid = |reason: String| format!("{} is not a valid time setting: {}", s, reason);
+        let options = format!(" {}", s);
+        let ((options, _), _) = And2::<MoveOptionsParser, NothingParser>::parse_from(&options)
+            .map_err(|error| invalid(error.to_string()))?;
+        match options {
+            MoveOptions {
+                time_setting: Some(time_setting),
+                win_length: None,
+            } => Ok(time_setting),
+            _ => Err(invalid("expected time options only".to_string())),
+        }
+    }
+}
+
+pub struct MillisTokenParser;
 const MILLIS: &str = "ms";
+generate_token_parser!(MILLIS, MillisTokenParser);
+
+pub struct SecondsTokenParser;
+const SECONDS: &str = "s";
+generate_token_parser!(SECONDS, SecondsTokenParser);
+
+pub struct MinutesTokenParser;
+const MINUTES: &str = "m";
+generate_token_parser!(MINUTES, MinutesTokenParser);
+
+pub struct ColonTokenParser;
+const COLON: &str = ":";
+generate_token_parser!(COLON, ColonTokenParser);
+
+pub type TimeUnitParser = Or3<MillisTokenParser, SecondsTokenParser, MinutesTokenParser>;
+
+/// Parses `<unit>:<quantity>` and returns the quantity in milliseconds.
+pub struct TimeParser;
 
 impl Parser<Number> for TimeParser {
-    fn parse_from(val: &String) -> ParseResult<Number> {
-        if val.starts_with(MILLIS) {
-            return Number::parse_from(&val[MILLIS.len() + 1..].to_string())
-                .map(|(quantity, remaining)| (quantity, remaining))
-                .map_err(|error| format!("Error parsing time: {}", error));
-        }
+    fn parse_from(val: &str) -> ParseResult<'_, Number> {
+        let ((unit, (_, quantity)), remaining) =
+            And3::<TimeUnitParser, ColonTokenParser, Number>::parse_from(val)?;
+
+        let millis_per_unit = match unit {
+            Either::Left(_) => 1,
+            Either::Right(Either::Left(_)) => 1_000,
+            Either::Right(Either::Right(_)) => 60_000,
+        };
 
-        Err("Invalid time unit".to_string())
+        quantity
+            .0
+            .checked_mul(millis_per_unit)
+            .map(|millis| (Number(millis), remaining))
+            .ok_or_else(|| ParseError::invalid("time is too large", val))
     }
 }
 
 pub type TotalTimeParser = And3<TimeTokenParser, WhiteSpaceParser, TimeParser>;
 pub type TimeRemainingParser = And3<TimeRemainingTokenParser, WhiteSpaceParser, TimeParser>;
+pub type IncrementParser = And3<IncrementTokenParser, WhiteSpaceParser, TimeParser>;
+pub type MovesToGoParser = And3<MovesToGoTokenParser, WhiteSpaceParser, Number>;
 
+#[cfg(test)]
 mod test_time_parser {
-    use crate::parser::{number_parser::Number, Parser};
+    use crate::parser::{number_parser::Number, ParseError, Parser};
 
-    use super::TotalTimeParser;
+    use super::{IncrementParser, TimeParser, TimeSetting, TotalTimeParser};
 
     #[test]
     fn parse_valid_time() {
-        let time_string = String::from("time ms:1000");
-        let res = TotalTimeParser::parse_from(&time_string);
+        let time_string = "time ms:1000";
+        let res = TotalTimeParser::parse_from(time_string);
+        assert_eq!(res, Ok((("time", (" ", Number(1000))), "")));
+    }
+
+    #[test]
+    fn parse_time_in_other_units() {
+        assert_eq!(TimeParser::parse_from("s:3"), Ok((Number(3000), "")));
+        assert_eq!(TimeParser::parse_from("m:2 x"), Ok((Number(120000), " x")));
+    }
+
+    #[test]
+    fn parse_increment() {
+        let res = IncrementParser::parse_from("increment s:2");
+        assert_eq!(res, Ok((("increment", (" ", Number(2000))), "")));
+    }
+
+    #[test]
+    fn err_on_unknown_unit() {
+        let res = TimeParser::parse_from("h:1");
+        assert_eq!(
+            res,
+            Err(ParseError::expected_one_of(&["ms", "s", "m"], "h:1"))
+        );
+    }
+
+    #[test]
+    fn err_on_missing_colon() {
+        let res = TimeParser::parse_from("ms1000");
+        assert_eq!(res, Err(ParseError::expected(":", "1000").shift(2)));
+    }
+
+    #[test]
+    fn err_on_overflowing_time() {
+        let res = TimeParser::parse_from("m:100000000");
         assert_eq!(
             res,
-            Ok((
-                ("time".to_string(), (" ".to_string(), Number(1000))),
-                "".to_string()
-            ))
+            Err(ParseError::invalid("time is too large", "m:100000000"))
+        );
+    }
+
+    #[test]
+    fn allocate_time_for_move() {
+        let clock = TimeSetting::Clock {
+            remaining: Number(60000),
+            increment: Number(500),
+            moves_to_go: Some(Number(10)),
+        };
+
+        assert_eq!(
+            TimeSetting::TotalTime(Number(100)).allocate(),
+            Some(Number(100))
+        );
+        assert_eq!(
+            TimeSetting::TimeRemaining(Number(2000)).allocate(),
+            Some(Number(100))
+        );
+        assert_eq!(clock.allocate(), Some(Number(6500)));
+        assert_eq!(TimeSetting::Infinite.allocate(), None);
+    }
+
+    #[test]
+    fn never_allocate_more_than_remaining() {
+        let clock = TimeSetting::Clock {
+            remaining: Number(1000),
+            increment: Number(500),
+            moves_to_go: Some(Number(1)),
+        };
+
+        assert_eq!(clock.allocate(), Some(Number(1000)));
+    }
+}
+
+#[cfg(test)]
+mod test_time_setting {
+    use crate::parser::{number_parser::Number, TimeSetting};
+
+    #[test]
+    fn reads_a_clock() {
+        assert_eq!(
+            "time-remaining s:60 increment s:1".parse::<TimeSetting>(),
+            Ok(TimeSetting::Clock {
+                remaining: Number(60_000),
+                increment: Number(1_000),
+                moves_to_go: None,
+            })
         );
     }
+
+    #[test]
+    fn reads_nothing_as_no_limit() {
+        assert_eq!("".parse::<TimeSetting>(), Ok(TimeSetting::Infinite));
+    }
+
+    #[test]
+    fn rejects_other_move_options() {
+        assert!("win-length 5".parse::<TimeSetting>().is_err());
+    }
 }
@@ -1,12 +1,12 @@
 #[macro_export]
 macro_rules! generate_token_parser {
     ($token:expr, $name:ident) => {
-        impl Parser<String> for $name {
-            fn parse_from(val: &String) -> ParseResult<String> {
+        impl $crate::parser::Parser<&'static str> for $name {
+            fn parse_from(val: &str) -> $crate::parser::ParseResult<'_, &'static str> {
                 if val.starts_with($token) {
-                    Ok(($token.to_string(), val[$token.len()..].to_string()))
+                    Ok(($token, &val[$token.len()..]))
                 } else {
-                    Err(format!("Could not find token: {}", $token))
+                    Err($crate::parser::ParseError::expected($token, val))
                 }
             }
         }
@@ -15,17 +15,27 @@ macro_rules! generate_token_parser {
 
 #[cfg(test)]
 mod test_generic_token_parser {
-    use crate::parser::parse_result::ParseResult;
     use crate::parser::Parser;
 
     #[test]
     pub fn test_dummy_token_parser() {
         struct DummyTokenParser;
         generate_token_parser!("dummy", DummyTokenParser);
-        let dummy_string = "dummy 123".to_string();
+        let dummy_string = "dummy 123";
 
-        let parse_result = DummyTokenParser::parse_from(&dummy_string);
+        let parse_result = DummyTokenParser::parse_from(dummy_string);
 
-        assert_eq!(parse_result, Ok(("dummy".to_string(), " 123".to_string())));
+        assert_eq!(parse_result, Ok(("dummy", " 123")));
+    }
+
+    #[test]
+    pub fn test_remaining_input_is_borrowed() {
+        struct DummyTokenParser;
+        generate_token_parser!("dummy", DummyTokenParser);
+        let dummy_string = "dummy 123";
+
+        let (_, remaining) = DummyTokenParser::parse_from(dummy_string).unwrap();
+
+        assert_eq!(remaining.as_ptr(), dummy_string[5..].as_ptr());
     }
 }
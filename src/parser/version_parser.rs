@@ -1,8 +1,8 @@
 use crate::generate_token_parser;
 
 use super::{
-    and_parser::And3, number_parser::Number, whitespace_parser::WhiteSpaceParser, ParseResult,
-    Parser,
+    and_parser::And3, number_parser::Number, whitespace_parser::WhiteSpaceParser, Digit,
+    ParseError, ParseResult, Parser,
 };
 
 #[derive(PartialEq, Eq, Debug)]
@@ -15,8 +15,11 @@ pub struct VersionTokenParser;
 generate_token_parser!(VERSION, VersionTokenParser);
 
 impl Parser<Number> for VersionNumberParser {
-    fn parse_from(val: &String) -> ParseResult<Number> {
-        Number::try_from(val).map(|n| (n, "".to_string()))
+    fn parse_from(val: &str) -> ParseResult<'_, Number> {
+        Number::try_from(val).map(|n| (n, "")).map_err(|reason| {
+            let offset = val.find(|c| !Digit::is_digit(&c)).unwrap_or(0);
+            ParseError::invalid(reason, &val[offset..]).shift(offset)
+        })
     }
 }
 
@@ -24,42 +27,52 @@ pub type VersionParser = And3<VersionTokenParser, WhiteSpaceParser, VersionNumbe
 
 #[cfg(test)]
 mod test_version_token_parser {
-    use crate::parser::{version_parser::VERSION, Parser};
+    use crate::parser::{version_parser::VERSION, ParseError, Parser};
 
     use super::VersionTokenParser;
 
     #[test]
     fn parse_version_token() {
-        let version_string = "version 1".to_string();
-        let res = VersionTokenParser::parse_from(&version_string);
-        assert_eq!(Ok((VERSION.to_string(), " 1".to_string())), res);
+        let version_string = "version 1";
+        let res = VersionTokenParser::parse_from(version_string);
+        assert_eq!(Ok((VERSION, " 1")), res);
     }
 
     #[test]
     fn error_invalid_token() {
-        let invalid_version_string = "v 1.1.1".to_string();
-        let res = VersionTokenParser::parse_from(&invalid_version_string);
-        assert_eq!(Err(String::from("Could not find token: version")), res);
+        let invalid_version_string = "v 1.1.1";
+        let res = VersionTokenParser::parse_from(invalid_version_string);
+        assert_eq!(
+            Err(ParseError::expected(VERSION, invalid_version_string)),
+            res
+        );
     }
 }
 
 #[cfg(test)]
 mod test_version_number_parser {
     use super::Number;
-    use crate::parser::{version_parser::VersionNumberParser, Parser};
+    use crate::parser::{version_parser::VersionNumberParser, ParseError, Parser};
 
     #[test]
     fn parse_version_number() {
-        let version_number = "1".to_string();
-        let res = VersionNumberParser::parse_from(&version_number);
-        assert_eq!(Ok((Number(1), "".to_string())), res);
+        let version_number = "1";
+        let res = VersionNumberParser::parse_from(version_number);
+        assert_eq!(Ok((Number(1), "")), res);
     }
 
     #[test]
     fn error_for_invalid_number() {
-        let version_number = "1x".to_string();
-        let res = VersionNumberParser::parse_from(&version_number);
-        assert_eq!(Err("x is not a digit".to_string()), res);
+        let version_number = "1x";
+        let res = VersionNumberParser::parse_from(version_number);
+        assert_eq!(
+            Err(ParseError::Invalid {
+                offset: 1,
+                reason: "x is not a digit".to_string(),
+                found: "x".to_string()
+            }),
+            res
+        );
     }
 }
 
@@ -71,14 +84,8 @@ mod test_version_parser {
 
     #[test]
     fn parse_version() {
-        let version_string = "version 2".to_string();
-        let res = VersionParser::parse_from(&version_string);
-        assert_eq!(
-            Ok((
-                (VERSION.to_string(), (" ".to_string(), Number(2))),
-                "".to_string()
-            )),
-            res
-        );
+        let version_string = "version 2";
+        let res = VersionParser::parse_from(version_string);
+        assert_eq!(Ok(((VERSION, (" ", Number(2))), "")), res);
     }
 }
@@ -1,6 +1,4 @@
 use crate::generate_token_parser;
-use crate::parser::parse_result::ParseResult;
-use crate::parser::Parser;
 
 const WHITESPACE: &str = " ";
 pub struct WhiteSpaceParser;
@@ -15,10 +13,7 @@ mod whitespace_parser_test {
 
     #[test]
     fn should_parse_string_starting_with_whitespace() {
-        let val = " 234".to_string();
-        assert_eq!(
-            Ok((WHITESPACE.to_string(), "234".to_string())),
-            WhiteSpaceParser::parse_from(&val)
-        );
+        let val = " 234";
+        assert_eq!(Ok((WHITESPACE, "234")), WhiteSpaceParser::parse_from(val));
     }
 }
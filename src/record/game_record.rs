@@ -0,0 +1,361 @@
+use std::fmt::Display;
+
+use crate::parser::{BestMove, Board, BoardState, MultiMove, Player, Rules, TimeSetting};
+
+/// The result of a drawn game.
+pub const DRAW: &str = "draw";
+/// The name of a player a record does not name.
+const UNKNOWN: &str = "?";
+
+const BOARD: &str = "Board";
+const TO_MOVE: &str = "ToMove";
+const WIN_LENGTH: &str = "WinLength";
+const RULES: &str = "Rules";
+const TIME: &str = "Time";
+const X: &str = "X";
+const O: &str = "O";
+const RESULT: &str = "Result";
+const FORFEIT: &str = "Forfeit";
+
+/// How a game ended.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum GameEnd {
+    Won(Player),
+    Drawn,
+    /// The player made an illegal move, ran out of time or could not move at all, and loses.
+    Forfeited(Player, String),
+}
+
+impl GameEnd {
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            GameEnd::Won(player) => Some(*player),
+            GameEnd::Drawn => None,
+            GameEnd::Forfeited(player, _) => Some(player.opponent()),
+        }
+    }
+}
+
+/// A whole game: where it started and under which rules and time control, who played it,
+/// every turn played and how it ended.
+///
+/// Records are written as headers of the form `[Key "value"]`, one per line, followed by
+/// a blank line and the turns in move notation, the stones of a turn joined by commas:
+///
+/// ```text
+/// [Board "3_/3_/3_"]
+/// [ToMove "x"]
+/// [WinLength "3"]
+/// [X "t3elric-engine"]
+/// [O "greedy"]
+/// [Result "draw"]
+///
+/// b2 a1 c1 a3 a2 c3 b3 b1 c2
+/// ```
+///
+/// `Rules` holds the engine's command line flags for the variant and `Time` the time
+/// control as written in a `move` command; both are left out when there are none. `Result`
+/// is `x`, `o` or `draw`, and a `Forfeit` header says why the loser forfeited.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct GameRecord {
+    /// The starting position, with the rules and the time control of the game.
+    pub start: BoardState,
+    pub x: String,
+    pub o: String,
+    pub turns: Vec<MultiMove>,
+    pub end: GameEnd,
+}
+
+impl GameRecord {
+    /// The position before the first turn and after every turn, in the order they were
+    /// played, or why a turn was not legal.
+    pub fn replay(&self) -> Result<Vec<BoardState>, String> {
+        let mut positions = vec![self.start.clone()];
+        for (number, turn) in self.turns.iter().enumerate() {
+            let position = positions[positions.len() - 1].clone();
+            let player = position.player_to_move;
+            let next = position
+                .apply_turn(turn)
+                .map_err(|reason| format!("Turn {}: {} {}", number + 1, player, reason))?;
+            positions.push(next);
+        }
+        Ok(positions)
+    }
+
+    /// Every position of the game before its end, each written as the board in T3N, the
+    /// player to move and the result for X: `1` won, `0` drawn or `-1` lost, e.g.
+    /// `3_/_x_/3_ o 1`. These are the training samples for a network scorer.
+    pub fn samples(&self) -> Vec<String> {
+        let result = match self.end.winner() {
+            Some(Player::X) => 1,
+            Some(Player::O) => -1,
+            None => 0,
+        };
+        let mut positions = self.replay().unwrap_or_default();
+        if matches!(self.end, GameEnd::Won(_) | GameEnd::Drawn) {
+            positions.pop();
+        }
+        positions
+            .iter()
+            .map(|position| format!("{} {} {}", position.board, position.player_to_move, result))
+            .collect()
+    }
+
+    /// Reads every record in `text`, one after the other. A header after the blank line
+    /// that ends a record's headers starts the next record, even if the first record has
+    /// no turns.
+    pub fn read_all(text: &str) -> Result<Vec<GameRecord>, String> {
+        let mut records = Vec::new();
+        let mut headers = Vec::new();
+        let mut turns = Vec::new();
+        let mut headers_ended = false;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let invalid = |reason: String| format!("Record line {}: {}", number + 1, reason);
+            if line.starts_with('[') {
+                if headers_ended {
+                    records.push(Self::from_parts(&headers, &turns)?);
+                    headers.clear();
+                    turns.clear();
+                    headers_ended = false;
+                }
+                headers.push(
+                    header(line)
+                        .ok_or_else(|| invalid(format!("{} is not a valid header.", line)))?,
+                );
+            } else if line.is_empty() {
+                headers_ended = !headers.is_empty();
+            } else {
+                headers_ended = true;
+                for turn in line.split_whitespace() {
+                    turns.push(multi_move(turn).map_err(invalid)?);
+                }
+            }
+        }
+        if !headers.is_empty() {
+            records.push(Self::from_parts(&headers, &turns)?);
+        }
+        Ok(records)
+    }
+
+    fn from_parts(headers: &[(String, String)], turns: &[MultiMove]) -> Result<Self, String> {
+        let value = |key: &str| {
+            headers
+                .iter()
+                .find(|(header, _)| header == key)
+                .map(|(_, value)| value.as_str())
+        };
+        let required = |key: &str| value(key).ok_or_else(|| format!("Missing {} header", key));
+
+        let board = required(BOARD)?
+            .parse::<Board>()
+            .map_err(|error| error.to_string())?;
+        let player = value(TO_MOVE).unwrap_or("x").parse::<Player>()?;
+        let win_length = match value(WIN_LENGTH) {
+            Some(win_length) => Some(
+                win_length
+                    .parse::<u32>()
+                    .map_err(|_| format!("{} is not a valid win length.", win_length))?,
+            ),
+            None => None,
+        };
+        let rules = Rules::from_args(
+            value(RULES)
+                .unwrap_or("")
+                .split_whitespace()
+                .map(str::to_string),
+        )?;
+        let time_setting = value(TIME).unwrap_or("").parse::<TimeSetting>()?;
+        let winner = match required(RESULT)? {
+            DRAW => None,
+            result => Some(result.parse::<Player>()?),
+        };
+        let end = match (winner, value(FORFEIT)) {
+            (Some(winner), Some(reason)) => {
+                GameEnd::Forfeited(winner.opponent(), reason.to_string())
+            }
+            (Some(winner), None) => GameEnd::Won(winner),
+            (None, _) => GameEnd::Drawn,
+        };
+
+        let record = GameRecord {
+            start: BoardState::new(player, board, time_setting, win_length).with_rules(rules),
+            x: value(X).unwrap_or(UNKNOWN).to_string(),
+            o: value(O).unwrap_or(UNKNOWN).to_string(),
+            turns: turns.to_vec(),
+            end,
+        };
+        record.replay()?;
+        Ok(record)
+    }
+}
+
+/// Splits `[Key "value"]` into its key and value.
+fn header(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.strip_prefix('[')?.strip_suffix(']')?.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((key.to_string(), value.to_string()))
+}
+
+/// Reads the stones of one turn, e.g. `j10,k11`.
+fn multi_move(turn: &str) -> Result<MultiMove, String> {
+    turn.split(',')
+        .map(|mv| mv.parse::<BestMove>().map_err(|error| error.to_string()))
+        .collect::<Result<Vec<_>, _>>()
+        .map(MultiMove::new)
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut write_header =
+            |key: &str, value: &dyn Display| writeln!(f, "[{} \"{}\"]", key, value);
+        write_header(BOARD, &self.start.board)?;
+        write_header(TO_MOVE, &self.start.player_to_move)?;
+        write_header(WIN_LENGTH, &self.start.win_length)?;
+        if self.start.rules != Rules::default() {
+            write_header(RULES, &self.start.rules)?;
+        }
+        if self.start.time_setting != TimeSetting::Infinite {
+            write_header(TIME, &self.start.time_setting)?;
+        }
+        write_header(X, &self.x)?;
+        write_header(O, &self.o)?;
+        match self.end.winner() {
+            Some(winner) => write_header(RESULT, &winner)?,
+            None => write_header(RESULT, &DRAW)?,
+        }
+        if let GameEnd::Forfeited(_, reason) = &self.end {
+            write_header(FORFEIT, reason)?;
+        }
+
+        let turns = self
+            .turns
+            .iter()
+            .map(|turn| {
+                turn.moves()
+                    .iter()
+                    .map(|mv| format!("{}{}", mv.get_column_notation(), mv.get_row_notation()))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>();
+        writeln!(f)?;
+        writeln!(f, "{}", turns.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod test_game_record {
+    use crate::parser::{BestMove, Board, BoardState, MultiMove, Player, Rules, TimeSetting};
+
+    use super::{GameEnd, GameRecord};
+
+    fn record() -> GameRecord {
+        GameRecord {
+            start: BoardState::new(
+                Player::X,
+                "3_/3_/3_".parse::<Board>().unwrap(),
+                TimeSetting::Infinite,
+                None,
+            ),
+            x: "t3elric-engine".to_string(),
+            o: "greedy".to_string(),
+            turns: [(1, 1), (0, 0), (0, 2), (2, 0), (1, 0)]
+                .into_iter()
+                .map(|(x, y)| MultiMove::from(BestMove::new(x, y)))
+                .collect(),
+            end: GameEnd::Forfeited(Player::O, "greedy ran out of time".to_string()),
+        }
+    }
+
+    const RECORD: &str = "[Board \"3_/3_/3_\"]
+[ToMove \"x\"]
+[WinLength \"3\"]
+[X \"t3elric-engine\"]
+[O \"greedy\"]
+[Result \"x\"]
+[Forfeit \"greedy ran out of time\"]
+
+b2 a1 c1 a3 a2
+";
+
+    #[test]
+    fn writes_headers_and_turns() {
+        assert_eq!(record().to_string(), RECORD);
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        assert_eq!(GameRecord::read_all(RECORD), Ok(vec![record()]));
+    }
+
+    #[test]
+    fn reads_several_records_with_rules_and_clocks() {
+        let text = "[Board \"4_/4_/4_/4_\"]
+[Rules \"--stones-per-turn 1,2 --gravity\"]
+[Time \"time-remaining ms:60000 increment ms:1000\"]
+[Result \"draw\"]
+
+a4 b4,c4
+
+[Board \"3_/3_/3_\"]
+[Result \"o\"]
+";
+        let records = GameRecord::read_all(text).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].turns[1].moves().len(), 2);
+        assert!(records[0].start.rules.gravity);
+        assert_eq!(
+            records[0].start.time_setting.to_string(),
+            "time-remaining ms:60000 increment ms:1000"
+        );
+        assert_eq!(records[0].x, "?");
+        assert_eq!(records[1].end, GameEnd::Won(Player::O));
+    }
+
+    #[test]
+    fn reads_back_a_record_without_turns() {
+        let mut forfeited = record();
+        forfeited.turns.clear();
+        forfeited.end = GameEnd::Forfeited(Player::X, "x made an illegal move".to_string());
+        let text = format!("{}\n{}\n", forfeited, record());
+        assert_eq!(GameRecord::read_all(&text), Ok(vec![forfeited, record()]));
+    }
+
+    #[test]
+    fn replays_the_positions_of_every_turn() {
+        let positions = record().replay().unwrap();
+        assert_eq!(positions.len(), 6);
+        assert_eq!(positions[2].board.to_string(), "o2_/_x_/3_");
+        assert_eq!(positions[5].player_to_move, Player::O);
+    }
+
+    #[test]
+    fn samples_every_position_with_the_result() {
+        let samples = record().samples();
+        assert_eq!(samples.len(), 6);
+        assert_eq!(samples[0], "3_/3_/3_ x 1");
+        assert_eq!(samples[5], "o_x/2x_/o2_ o 1");
+    }
+
+    #[test]
+    fn rejects_an_illegal_turn() {
+        let text = "[Board \"3_/3_/3_\"]\n[Result \"x\"]\n\nb2 b2\n";
+        let error = GameRecord::read_all(text).unwrap_err();
+        assert!(
+            error.starts_with("Turn 2: o played an illegal move"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn writes_the_variant_rules() {
+        let mut record = record();
+        record.start = record.start.with_rules(Rules {
+            misere: true,
+            ..Rules::default()
+        });
+        assert!(record.to_string().contains("[Rules \"--misere\"]\n"));
+    }
+}
@@ -0,0 +1,3 @@
+mod game_record;
+
+pub use game_record::{GameEnd, GameRecord, DRAW};
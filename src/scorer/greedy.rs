@@ -1,9 +1,7 @@
 
 use crate::{
-    evaluator::{
-        Evaluator, GreedyEvaluator,
-    },
-    parser::Cell,
+    evaluator::{Evaluator, GreedyEvaluator, Weights},
+    parser::{BoardState, Player},
 };
 
 use super::Scorer;
@@ -14,9 +12,18 @@ pub struct GreedyScorer {
 
 impl Default for GreedyScorer {
     fn default() -> Self {
-        Self {
-            evaluator: Box::new(GreedyEvaluator::default()),
-        }
+        Self::new(Weights::default())
+    }
+}
+
+impl GreedyScorer {
+    pub fn new(weights: Weights) -> Self {
+        Self::with_evaluator(Box::new(GreedyEvaluator::new(weights)))
+    }
+
+    /// Scores every playable cell with `evaluator` instead of the greedy evaluator.
+    pub fn with_evaluator(evaluator: Box<dyn Evaluator>) -> Self {
+        Self { evaluator }
     }
 }
 
@@ -26,25 +33,37 @@ impl Scorer for GreedyScorer {
             return 10000f32 * f32::from(player as i16);
         }
 
-        let rows = board_state.board.get_rows();
-        let (rlen, clen) = (rows.len(), rows[0].len());
         let mut cum_score = 0f32;
         let player = board_state.player_to_move;
-        for i in 0..rlen {
-            for j in 0..clen {
-                let cell = rows[i][j];
+        for best_move in board_state.get_possible_moves() {
+            let (i, j) = (best_move.row() as usize, best_move.column() as usize);
 
-                if cell != Cell::Playable {
-                    continue;
-                }
+            let player_score =
+                (self.evaluator.score(&board_state, i, j, player)) * (f32::from(player as i16));
 
-                let player_score =
-                    (self.evaluator.score(&board_state, i, j, player)) * (f32::from(player as i16));
+            cum_score += player_score;
+        }
 
-                cum_score += player_score;
-            }
+        // Under misère rules lines are to be avoided, so line potential counts against.
+        if board_state.rules.misere {
+            -cum_score
+        } else {
+            cum_score
         }
-        cum_score
+    }
+
+    /// The greedy score adds up the moves of the player to move, and weighs blocking the
+    /// opponent's lines above extending its own, so it rates the side to move higher the
+    /// more its opponent threatens. X is therefore ahead by as much as O's score with O to
+    /// move outweighs X's with X to move, out of both scores together.
+    fn weigh(&self, board_state: &BoardState) -> (f32, f32) {
+        let score = |player| {
+            let mut state = board_state.clone();
+            state.player_to_move = player;
+            self.score(&mut state)
+        };
+        let (x, o) = (score(Player::X), -score(Player::O));
+        (o - x, x + o)
     }
 }
 
@@ -54,7 +73,7 @@ mod test_greedy_scorer {
         parser::{
             BestMove, Board, BoardState,
             Cell::{Playable, Played},
-            Player, TimeSetting,
+            Player, Rules, TimeSetting,
         },
         scorer::Scorer,
         solver::Solver,
@@ -111,4 +130,42 @@ mod test_greedy_scorer {
     }
 
     // w / max((w - s), 1)
+
+    #[test]
+    fn should_invert_score_under_misere() {
+        let board = Board::new(vec![
+            vec![Played(Player::O), Playable, Playable],
+            vec![Playable, Played(Player::O), Played(Player::O)],
+            vec![Playable, Played(Player::X), Played(Player::X)],
+        ]);
+        let mut normal = BoardState::new(Player::X, board.clone(), TimeSetting::Infinite, None);
+        let mut misere =
+            BoardState::new(Player::X, board, TimeSetting::Infinite, None).with_rules(Rules {
+                misere: true,
+                ..Rules::default()
+            });
+
+        let normal_score = GreedyScorer::default().score(&mut normal);
+        assert_eq!(GreedyScorer::default().score(&mut misere), -normal_score);
+    }
+
+    #[test]
+    fn should_score_completed_line_as_loss_under_misere() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Playable, Playable, Played(Player::O)],
+                vec![Played(Player::X), Played(Player::X), Played(Player::O)],
+                vec![Playable, Playable, Played(Player::O)],
+            ]),
+            TimeSetting::Infinite,
+            None,
+        )
+        .with_rules(Rules {
+            misere: true,
+            ..Rules::default()
+        });
+        let score = GreedyScorer::default().score(&mut board_state);
+        assert!(score > 3000f32);
+    }
 }
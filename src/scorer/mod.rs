@@ -1,11 +1,29 @@
-use crate::parser::BoardState;
+use crate::parser::{BoardState, Player};
 
 mod greedy;
 mod dumb;
+mod network;
 
+/// The score of a game won by X; negated for O.
+const WON: f32 = 10000.0;
+
+/// Rates positions for X: the higher the score, the better the position is for X and the
+/// worse for O, with a game won by X scoring 10000 and one won by O -10000.
 pub trait Scorer {
+    fn score(&self, board_state: &mut BoardState) -> f32;
 
-    fn score(&self, board_state: &mut BoardState)  -> f32;
+    /// How far X is ahead in `board_state` whoever is to move, negative when O is, and how
+    /// much there is to be ahead by. Averages the scores with either player to move, out of
+    /// the score of a won game.
+    fn weigh(&self, board_state: &BoardState) -> (f32, f32) {
+        let score = |player| {
+            let mut state = board_state.clone();
+            state.player_to_move = player;
+            self.score(&mut state)
+        };
+        ((score(Player::X) + score(Player::O)) / 2f32, WON)
+    }
 }
 
-pub use greedy::GreedyScorer;
\ No newline at end of file
+pub use greedy::GreedyScorer;
+pub use network::{Network, NetworkScorer, PLANES};
@@ -0,0 +1,364 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::parser::{BoardState, Cell, Player};
+
+use super::{Scorer, WON};
+
+/// The planes a board is fed to the network as: X's stones, O's stones, the empty cells,
+/// the cells that cannot be played, and a plane of ones when X is to move.
+pub const PLANES: usize = 5;
+/// What a network output of 1 is worth, well below the score of a game already won.
+const SCALE: f32 = 1000.0;
+
+const HEADER: &str = "network";
+const CONVOLUTION: &str = "convolution";
+const DENSE: &str = "dense";
+
+/// A layer of a [`Network`] with `outputs` biases and a weight for every pair of input
+/// and output, 9 of them for the 3x3 kernel of a convolution.
+#[derive(PartialEq, Debug, Clone)]
+struct Layer {
+    convolution: bool,
+    inputs: usize,
+    outputs: usize,
+    weights: Vec<f32>,
+    biases: Vec<f32>,
+}
+
+impl Layer {
+    fn weight_count(&self) -> usize {
+        let kernel = if self.convolution { 9 } else { 1 };
+        self.inputs * self.outputs * kernel
+    }
+
+    /// Runs the convolution over `values`, `inputs` planes of `height` by `width` cells,
+    /// counting the cells beyond the edges as 0. The rows are stacked layers of
+    /// `layer_height` rows each, and the kernel does not reach from one layer into the next.
+    fn convolve(
+        &self,
+        values: &[f32],
+        height: usize,
+        width: usize,
+        layer_height: usize,
+    ) -> Vec<f32> {
+        let size = height * width;
+        let mut planes = vec![0.0; self.outputs * size];
+        for output in 0..self.outputs {
+            for row in 0..height {
+                for column in 0..width {
+                    let mut sum = self.biases[output];
+                    for input in 0..self.inputs {
+                        for (tap, weight) in self.kernel(output, input).iter().enumerate() {
+                            let (r, c) = (row + tap / 3, column + tap % 3);
+                            if (1..=height).contains(&r)
+                                && (r - 1) / layer_height == row / layer_height
+                                && (1..=width).contains(&c)
+                            {
+                                sum += weight * values[input * size + (r - 1) * width + c - 1];
+                            }
+                        }
+                    }
+                    planes[output * size + row * width + column] = sum;
+                }
+            }
+        }
+        planes
+    }
+
+    fn kernel(&self, output: usize, input: usize) -> &[f32] {
+        let start = (output * self.inputs + input) * 9;
+        &self.weights[start..start + 9]
+    }
+
+    fn dense(&self, values: &[f32]) -> Vec<f32> {
+        (0..self.outputs)
+            .map(|output| {
+                let weights = &self.weights[output * self.inputs..(output + 1) * self.inputs];
+                let sum: f32 = weights.iter().zip(values).map(|(w, v)| w * v).sum();
+                sum + self.biases[output]
+            })
+            .collect()
+    }
+}
+
+/// A small convolutional network that rates a position from -1, won by O, to 1, won by X.
+///
+/// The board's planes go through 3x3 convolutions over the rows and columns of each of its
+/// layers, are averaged over the board into one value per channel and go through dense
+/// layers down to a single output. Every layer but the last is followed by a ReLU and the
+/// last by tanh. Averaging the board away lets one network rate boards of any size.
+///
+/// Written as a `network` line followed by every layer: a line naming it with its inputs
+/// and outputs, e.g. `convolution 5 8` or `dense 8 1`, a line of its weights and a line of
+/// its biases. Convolution weights are ordered by output, input, then row and column of
+/// the kernel, and dense weights by output, then input.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Network {
+    layers: Vec<Layer>,
+}
+
+impl Network {
+    pub fn evaluate(&self, board_state: &BoardState) -> f32 {
+        let rows = board_state.board.get_rows();
+        let (height, width) = (rows.len(), board_state.board.column_count());
+        let layer_height = board_state.board.height();
+        let x_to_move = board_state.player_to_move == Player::X;
+        let mut values = (0..PLANES)
+            .flat_map(|plane| {
+                rows.iter().flatten().map(move |cell| {
+                    let on = match plane {
+                        0 => *cell == Cell::Played(Player::X),
+                        1 => *cell == Cell::Played(Player::O),
+                        2 => *cell == Cell::Playable,
+                        3 => *cell == Cell::NonPlayable,
+                        _ => x_to_move,
+                    };
+                    if on {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+            })
+            .collect::<Vec<f32>>();
+
+        let mut pooled = false;
+        for (index, layer) in self.layers.iter().enumerate() {
+            values = if layer.convolution {
+                layer.convolve(&values, height, width, layer_height)
+            } else {
+                if !pooled {
+                    values = average(&values, layer.inputs);
+                    pooled = true;
+                }
+                layer.dense(&values)
+            };
+            let activation = if index + 1 == self.layers.len() {
+                f32::tanh
+            } else {
+                |value: f32| value.max(0.0)
+            };
+            values
+                .iter_mut()
+                .for_each(|value| *value = activation(*value));
+        }
+        values[0]
+    }
+}
+
+/// Averages each of `channels` planes into one value.
+fn average(values: &[f32], channels: usize) -> Vec<f32> {
+    let size = (values.len() / channels).max(1);
+    values
+        .chunks(size)
+        .map(|plane| plane.iter().sum::<f32>() / size as f32)
+        .collect()
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        if lines.next() != Some(HEADER) {
+            return Err(format!("A network starts with a {} line", HEADER));
+        }
+
+        let mut layers = Vec::new();
+        while let Some(line) = lines.next() {
+            let number = layers.len() + 1;
+            let invalid = || format!("Layer {}: {} is not a valid layer.", number, line);
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (convolution, inputs, outputs) = match fields[..] {
+                [kind, inputs, outputs] if kind == CONVOLUTION || kind == DENSE => (
+                    kind == CONVOLUTION,
+                    inputs.parse::<usize>().map_err(|_| invalid())?,
+                    outputs.parse::<usize>().map_err(|_| invalid())?,
+                ),
+                _ => return Err(invalid()),
+            };
+            let mut numbers = |name: &str| {
+                lines
+                    .next()
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(|number| number.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("Layer {}: invalid {}", number, name))
+            };
+            let layer = Layer {
+                convolution,
+                inputs,
+                outputs,
+                weights: numbers("weights")?,
+                biases: numbers("biases")?,
+            };
+            if layer.weights.len() != layer.weight_count() || layer.biases.len() != outputs {
+                return Err(format!(
+                    "Layer {}: expected {} weights and {} biases, found {} and {}",
+                    number,
+                    layer.weight_count(),
+                    outputs,
+                    layer.weights.len(),
+                    layer.biases.len()
+                ));
+            }
+            layers.push(layer);
+        }
+
+        validate(&layers)?;
+        Ok(Network { layers })
+    }
+}
+
+/// Checks that each layer takes as many inputs as the one before puts out, that the
+/// convolutions come first and that the network ends in a single output.
+fn validate(layers: &[Layer]) -> Result<(), String> {
+    let mut channels = PLANES;
+    let mut dense = false;
+    for (index, layer) in layers.iter().enumerate() {
+        if layer.convolution && dense {
+            return Err(format!(
+                "Layer {}: convolutions must come before dense layers",
+                index + 1
+            ));
+        }
+        if layer.inputs != channels {
+            return Err(format!(
+                "Layer {}: expected {} inputs, found {}",
+                index + 1,
+                channels,
+                layer.inputs
+            ));
+        }
+        dense = !layer.convolution;
+        channels = layer.outputs;
+    }
+    match layers.last() {
+        Some(layer) if !layer.convolution && layer.outputs == 1 => Ok(()),
+        _ => Err("The last layer must be a dense layer with 1 output".to_string()),
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |numbers: &[f32]| {
+            numbers
+                .iter()
+                .map(f32::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        writeln!(f, "{}", HEADER)?;
+        for layer in &self.layers {
+            let kind = if layer.convolution {
+                CONVOLUTION
+            } else {
+                DENSE
+            };
+            writeln!(f, "{} {} {}", kind, layer.inputs, layer.outputs)?;
+            writeln!(f, "{}", join(&layer.weights))?;
+            writeln!(f, "{}", join(&layer.biases))?;
+        }
+        Ok(())
+    }
+}
+
+/// Scores positions with a [`Network`], and won games like the other scorers do.
+pub struct NetworkScorer {
+    network: Network,
+}
+
+impl NetworkScorer {
+    pub fn new(network: Network) -> Self {
+        Self { network }
+    }
+}
+
+impl Scorer for NetworkScorer {
+    fn score(&self, board_state: &mut BoardState) -> f32 {
+        if let Some(player) = board_state.get_winner() {
+            return WON * f32::from(player as i16);
+        }
+        SCALE * self.network.evaluate(board_state)
+    }
+}
+
+#[cfg(test)]
+mod test_network {
+    use crate::{
+        parser::{Board, BoardState, Player, TimeSetting},
+        scorer::Scorer,
+    };
+
+    use super::{Network, NetworkScorer};
+
+    /// Counts X's stones with the centre of its kernel and averages them over the board.
+    const STONE_COUNTER: &str = "network
+convolution 5 1
+0 0 0 0 1 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0  0 0 0 0 0 0 0 0 0
+0
+dense 1 1
+1
+0
+";
+
+    fn board_state(board: &str) -> BoardState {
+        BoardState::new(
+            Player::O,
+            board.parse::<Board>().unwrap(),
+            TimeSetting::Infinite,
+            None,
+        )
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let network = STONE_COUNTER.parse::<Network>().unwrap();
+        assert_eq!(network.to_string().parse::<Network>(), Ok(network));
+    }
+
+    #[test]
+    fn rates_a_position_through_its_layers() {
+        let network = STONE_COUNTER.parse::<Network>().unwrap();
+        assert_eq!(
+            network.evaluate(&board_state("x2_/3_/3_")),
+            (1.0f32 / 9.0).tanh()
+        );
+        assert_eq!(network.evaluate(&board_state("4_/4_")), 0.0);
+    }
+
+    #[test]
+    fn convolves_each_layer_of_a_board_on_its_own() {
+        // Counts X's stones in the row below each cell.
+        let network = STONE_COUNTER.replacen("0 0 0 0 1 0 0 0 0", "0 0 0 0 0 0 0 1 0", 1);
+        let network = network.parse::<Network>().unwrap();
+        assert_eq!(network.evaluate(&board_state("2_/2_|x_/2_")), 0.0);
+        assert_eq!(
+            network.evaluate(&board_state("2_/x_|2_/2_")),
+            (1.0f32 / 8.0).tanh()
+        );
+    }
+
+    #[test]
+    fn rejects_layers_that_do_not_fit() {
+        let network = STONE_COUNTER.replace("dense 1 1\n1", "dense 2 1\n1 1");
+        assert_eq!(
+            network.parse::<Network>(),
+            Err("Layer 2: expected 1 inputs, found 2".to_string())
+        );
+        assert_eq!(
+            "network\ndense 5 1\n1 1\n0".parse::<Network>(),
+            Err("Layer 1: expected 5 weights and 1 biases, found 2 and 1".to_string())
+        );
+    }
+
+    #[test]
+    fn scores_won_games_as_won() {
+        let scorer = NetworkScorer::new(STONE_COUNTER.parse::<Network>().unwrap());
+        assert_eq!(scorer.score(&mut board_state("3x/3_/o_o")), 10000.0);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::parser::{BestMove, BoardState, Cell};
+use crate::parser::BoardState;
 
 use super::Solver;
 
@@ -6,18 +6,9 @@ pub struct FirstMoveSolver;
 
 impl Solver for FirstMoveSolver {
     fn solve(&self, board_state: &mut BoardState) -> (Option<crate::parser::BestMove>, f32) {
-        let rows = &board_state.board.get_rows();
-        let (mut x, mut y) = (0, 0);
-        for row in rows {
-            for cell in row {
-                if *cell == Cell::Playable {
-                    return (Some(BestMove::new(y, x)), 100.0);
-                }
-                y += 1;
-            }
-            x += 1;
-            y = 0;
+        match board_state.get_possible_moves().into_iter().next() {
+            Some(best_move) => (Some(best_move), 100.0),
+            None => (None, -1f32),
         }
-        (None, -1f32)
     }
 }
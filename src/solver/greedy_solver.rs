@@ -1,85 +1,74 @@
 
 use crate::{
-    evaluator::{
-        ColumnEvaluator, DiagonalEvaluator, Evaluator, MapEvaluator, OpponentEvaluator,
-        ReduceEvaluator, RowEvaluator, WinningEvaluator,
-    },
-    parser::{BestMove, BoardState, Cell},
+    evaluator::{Evaluator, Pipeline, Weights},
+    parser::{BestMove, BoardState},
 };
 
-use super::Solver;
+use super::{MoveAnalysis, Solver};
 
 pub struct GreedySolver {
-    winning_evaluator: Box<dyn Evaluator>,
-    losing_evaluator: Box<dyn Evaluator>,
-    player_score_evaluator: Box<dyn Evaluator>,
-    player_sum_evaluator: Box<dyn Evaluator>,
+    evaluator: Box<dyn Evaluator>,
+    /// The longest line of the player's through a cell, to spot the moves that complete one.
+    line_evaluator: Box<dyn Evaluator>,
 }
 
 impl Default for GreedySolver {
     fn default() -> Self {
-        let win_evaluators: Vec<Box<dyn Evaluator>> = vec![
-            Box::new(WinningEvaluator::new(Box::new(ColumnEvaluator))),
-            Box::new(WinningEvaluator::new(Box::new(RowEvaluator))),
-            Box::new(WinningEvaluator::new(Box::new(DiagonalEvaluator))),
-        ];
-
-        let losing_evaluators: Vec<Box<dyn Evaluator>> = vec![
-            Box::new(WinningEvaluator::new(Box::new(OpponentEvaluator::new(
-                Box::new(ColumnEvaluator),
-            )))),
-            Box::new(WinningEvaluator::new(Box::new(OpponentEvaluator::new(
-                Box::new(RowEvaluator),
-            )))),
-            Box::new(WinningEvaluator::new(Box::new(OpponentEvaluator::new(
-                Box::new(DiagonalEvaluator),
-            )))),
-        ];
+        Self::new(Weights {
+            losing: 1.0,
+            ..Weights::default()
+        })
+    }
+}
 
-        let winning_evaluator: Box<dyn Evaluator> = Box::new(ReduceEvaluator::new(
-            win_evaluators,
-            Box::new(|x, y| f32::max(x, y)),
-        ));
-        let losing_evaluator: Box<dyn Evaluator> = Box::new(MapEvaluator::new(
-            Box::new(ReduceEvaluator::new(
-                losing_evaluators,
-                Box::new(|x, y| f32::max(x, y)),
-            )),
-            Box::new(|f| f * 1.0),
-        ));
+impl GreedySolver {
+    pub fn new(weights: Weights) -> Self {
+        Self::with_evaluator(Pipeline::greedy(&weights).build())
+    }
 
-        let player_score_evaluator = Box::new(ReduceEvaluator::new(
-            vec![
-                Box::new(ColumnEvaluator),
-                Box::new(RowEvaluator),
-                Box::new(DiagonalEvaluator),
-            ],
-            Box::new(|x, y| f32::max(x, y)),
-        ));
+    /// Picks moves by `evaluator` instead of the greedy evaluator.
+    pub fn with_evaluator(evaluator: Box<dyn Evaluator>) -> Self {
+        let lines = vec![
+            Pipeline::Column,
+            Pipeline::Row,
+            Pipeline::Diagonal,
+            Pipeline::Layer,
+        ];
+        Self {
+            evaluator,
+            line_evaluator: Pipeline::Max(lines).build(),
+        }
+    }
 
-        let player_sum_evaluator = Box::new(ReduceEvaluator::new(
-            vec![
-                Box::new(ColumnEvaluator),
-                Box::new(RowEvaluator),
-                Box::new(DiagonalEvaluator),
-            ],
-            Box::new(|x, y| x + y),
-        ));
+    /// How good playing `best_move` is for the player to move.
+    fn move_score(&self, board_state: &BoardState, best_move: &BestMove) -> f32 {
+        let (i, j) = (best_move.row() as usize, best_move.column() as usize);
+        let player = board_state.player_to_move;
 
-        Self {
-            winning_evaluator,
-            losing_evaluator,
-            player_score_evaluator,
-            player_sum_evaluator,
+        let line = self.line_evaluator.score(board_state, i, j, player);
+        let completes_line = line == (board_state.win_length as f32 - 1f32);
+        let score = self.evaluator.score(board_state, i, j, player);
+        if board_state.rules.misere {
+            // Completing a line loses, so take it only when there is nothing else, and
+            // otherwise prefer the moves that build the least.
+            if completes_line {
+                -COMPLETES_LINE
+            } else {
+                -score
+            }
+        } else if completes_line {
+            COMPLETES_LINE
+        } else {
+            score
         }
     }
 }
 
+/// The score of a move that completes a line.
+const COMPLETES_LINE: f32 = 1000.0;
+
 impl Solver for GreedySolver {
     fn solve(&self, board_state: &mut BoardState) -> (Option<BestMove>, f32) {
-        let rows = &board_state.board.get_rows();
-        let (rlen, clen) = (rows.len(), rows[0].len());
-
         // let no_of_moves = rows
         //     .into_iter()
         //     .flatten()
@@ -89,57 +78,47 @@ impl Solver for GreedySolver {
         // if rows[rlen / 2][clen / 2] == Cell::Playable && no_of_moves < 1 {
         //     return (Some(BestMove::new(rlen as u32 / 2, clen as u32 / 2)), 100.0);
         // }
-        let mut best_score = -1f32;
-        let (mut bx, mut by) = (-1, -1);
-        for i in 0..rlen {
-            for j in 0..clen {
-                let cell: &Cell = &rows[i][j];
-                if *cell != Cell::Playable {
-                    continue;
-                }
-
-                let scores = vec![
-                    self.player_score_evaluator.score(
-                        board_state,
-                        i,
-                        j,
-                        board_state.player_to_move,
-                    ),
-                    self.winning_evaluator
-                        .score(board_state, i, j, board_state.player_to_move),
-                    self.losing_evaluator
-                        .score(board_state, i, j, board_state.player_to_move),
-                    self.player_sum_evaluator
-                        .score(board_state, i, j, board_state.player_to_move),
-                ];
-
-                // println!("({}, {}) -> {:?}", i, j, scores);
-
-                if scores[0] == (board_state.win_length as f32 - 1f32) {
-                    return (Some(BestMove::new(i as u32, j as u32)), 1000.0);
-                };
-
-                let max_score = scores.iter().fold(0.0, |acc, x| f32::max(acc, *x));
+        let mut best_score = f32::NEG_INFINITY;
+        let mut best = None;
+        for best_move in board_state.get_possible_moves() {
+            let max_score = self.move_score(board_state, &best_move);
+            if max_score == COMPLETES_LINE {
+                return (Some(best_move), max_score);
+            }
 
-                if max_score > best_score {
-                    best_score = max_score;
-                    (bx, by) = (i as i32, j as i32);
-                }
+            if max_score > best_score {
+                best_score = max_score;
+                best = Some(best_move);
             }
         }
 
-        if (bx, by) == (-1, -1) {
-            (None, -1.0)
-        } else {
-            (Some(BestMove::new(bx as u32, by as u32)), best_score)
+        match best {
+            Some(best_move) => (Some(best_move), best_score),
+            None => (None, -1.0),
         }
     }
+
+    fn analyse(&self, board_state: &mut BoardState) -> Vec<MoveAnalysis> {
+        let mut analyses = board_state
+            .get_possible_moves()
+            .into_iter()
+            .map(|best_move| MoveAnalysis {
+                score: self.move_score(board_state, &best_move),
+                variation: vec![best_move.clone()],
+                best_move,
+            })
+            .collect::<Vec<_>>();
+        analyses.sort_by(|a, b| b.score.total_cmp(&a.score));
+        analyses
+    }
 }
 
 #[cfg(test)]
 mod test_greedy_solver {
     use crate::{
-        parser::{BestMove, Board, BoardState, Cell::Playable, Cell::Played, Player, TimeSetting},
+        parser::{
+            BestMove, Board, BoardState, Cell::Playable, Cell::Played, Player, Rules, TimeSetting,
+        },
         solver::Solver,
     };
 
@@ -292,4 +271,90 @@ mod test_greedy_solver {
         let best_move = GreedySolver::default().solve(&mut board_state).0;
         assert_eq!(best_move, Some(BestMove::new(3, 0)));
     }
+
+    #[test]
+    fn should_only_play_reachable_cells_with_gravity() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Playable, Playable, Playable, Playable],
+                vec![Playable, Playable, Playable, Playable],
+                vec![Playable, Played(Player::X), Playable, Playable],
+                vec![
+                    Played(Player::X),
+                    Played(Player::O),
+                    Played(Player::O),
+                    Playable,
+                ],
+            ]),
+            TimeSetting::Infinite,
+            Some(3),
+        )
+        .with_rules(Rules {
+            gravity: true,
+            ..Rules::default()
+        });
+        let best_move = GreedySolver::default().solve(&mut board_state).0;
+        assert!(board_state
+            .get_possible_moves()
+            .contains(&best_move.unwrap()));
+    }
+
+    #[test]
+    fn should_avoid_completing_a_line_under_misere() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Played(Player::X), Played(Player::X), Playable],
+                vec![Played(Player::O), Playable, Played(Player::O)],
+                vec![Playable, Played(Player::O), Played(Player::X)],
+            ]),
+            TimeSetting::Infinite,
+            Some(3),
+        )
+        .with_rules(Rules {
+            misere: true,
+            ..Rules::default()
+        });
+        let best_move = GreedySolver::default().solve(&mut board_state).0;
+        assert_eq!(best_move, Some(BestMove::new(2, 0)));
+    }
+
+    #[test]
+    fn should_complete_a_line_under_misere_when_forced() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Played(Player::X), Played(Player::X), Playable],
+                vec![Played(Player::O), Played(Player::X), Played(Player::O)],
+                vec![Played(Player::X), Played(Player::O), Played(Player::O)],
+            ]),
+            TimeSetting::Infinite,
+            Some(3),
+        )
+        .with_rules(Rules {
+            misere: true,
+            ..Rules::default()
+        });
+        let best_move = GreedySolver::default().solve(&mut board_state).0;
+        assert_eq!(best_move, Some(BestMove::new(0, 2)));
+    }
+
+    #[test]
+    fn should_analyse_the_winning_move_first() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Played(Player::O), Playable, Playable],
+                vec![Playable, Played(Player::O), Played(Player::O)],
+                vec![Playable, Played(Player::X), Played(Player::X)],
+            ]),
+            TimeSetting::Infinite,
+            None,
+        );
+        let analyses = GreedySolver::default().analyse(&mut board_state);
+        assert_eq!(analyses.len(), 4);
+        assert_eq!(analyses[0].best_move, BestMove::new(2, 0));
+        assert_eq!(analyses[0].variation, vec![BestMove::new(2, 0)]);
+    }
 }
@@ -1,23 +1,57 @@
+use std::cell::{Cell, RefCell};
+
 use crate::{
     evaluator::Evaluator,
     parser::{BestMove, BoardState},
     scorer::Scorer,
 };
 
-use super::Solver;
+use super::{MoveAnalysis, Solver};
 
 pub struct LookAheadSolver {
     scorer: Box<dyn Scorer>,
     depth: u32,
+    nodes: Cell<u64>,
+    multi_pv: usize,
+    variety: f32,
+    variations: RefCell<Vec<MoveAnalysis>>,
 }
 
 impl LookAheadSolver {
     /// Creates a new LookAheadSolver with the given solver and search depth.
     pub fn new(scorer: Box<dyn Scorer>, depth: u32) -> Self {
-        Self { scorer, depth }
+        Self {
+            scorer,
+            depth,
+            nodes: Cell::new(0),
+            multi_pv: 1,
+            variety: 0.0,
+            variations: RefCell::new(Vec::new()),
+        }
     }
 
-    /// Alpha-Beta pruning recursive function.
+    /// Searches the best `count` root moves with full windows instead of only the best
+    /// one, keeping their principal variations for [`Solver::take_variations`].
+    pub fn with_multi_pv(mut self, count: usize) -> Self {
+        self.multi_pv = count.max(1);
+        self
+    }
+
+    /// Plays any of the root moves that score within `margin` of the best one, picked at
+    /// random, rather than always the best. With multi-PV only the searched moves are
+    /// candidates; without it, every move within the margin is.
+    pub fn with_variety(mut self, margin: f32) -> Self {
+        self.variety = margin.max(0.0);
+        self
+    }
+
+    /// The number of positions searched since the solver was created.
+    pub fn nodes(&self) -> u64 {
+        self.nodes.get()
+    }
+
+    /// Alpha-Beta pruning recursive function. Returns the score with the line of play the
+    /// search expects from `board_state` on, best move first.
     fn alpha_beta(
         &self,
         board_state: &mut BoardState,
@@ -25,9 +59,10 @@ impl LookAheadSolver {
         mut alpha: f32,
         mut beta: f32,
         maximizing_player: bool,
-    ) -> (Option<BestMove>, f32) {
+    ) -> (Vec<BestMove>, f32) {
+        self.nodes.set(self.nodes.get() + 1);
         if depth == 0 || board_state.is_game_over() {
-            return (None, self.evaluate(board_state));
+            return (Vec::new(), self.evaluate(board_state));
         }
 
         let possible_moves = board_state.get_possible_moves();
@@ -35,34 +70,61 @@ impl LookAheadSolver {
         // If no possible moves, evaluate the board
         if possible_moves.is_empty() {
             let evaluation = self.evaluate(board_state);
-            return (None, evaluation);
+            return (Vec::new(), evaluation);
         }
 
-        let mut best_move = None;
+        // The stones of one turn can be placed in any order, so below the root only try
+        // them in board order; every other order reaches a position already searched.
+        let possible_moves = match board_state.placed_this_turn.last() {
+            Some(last) if depth < self.depth && !board_state.rules.gravity => {
+                let moves: Vec<BestMove> = possible_moves
+                    .into_iter()
+                    .filter(|m| (m.row(), m.column()) > (last.row(), last.column()))
+                    .collect();
+                if moves.is_empty() {
+                    let unreachable = if maximizing_player {
+                        f32::NEG_INFINITY
+                    } else {
+                        f32::INFINITY
+                    };
+                    return (Vec::new(), unreachable);
+                }
+                moves
+            }
+            _ => possible_moves,
+        };
+
+        let mut best_line = Vec::new();
 
         if maximizing_player {
             let mut max_eval = f32::NEG_INFINITY;
             for m in possible_moves {
                 let mut new_state = board_state.apply_move(&m);
-                let (_, eval) = self.alpha_beta(&mut new_state, depth - 1, alpha, beta, false);
+                let maximizing = new_state.player_to_move == board_state.player_to_move;
+                let (mut line, eval) =
+                    self.alpha_beta(&mut new_state, depth - 1, alpha, beta, maximizing);
                 if eval > max_eval {
                     max_eval = eval;
-                    best_move = Some(m.clone());
+                    line.insert(0, m);
+                    best_line = line;
                 }
                 alpha = alpha.max(eval);
                 if beta <= alpha {
                     break; // Beta cutoff
                 }
             }
-            (best_move, max_eval)
+            (best_line, max_eval)
         } else {
             let mut min_eval = f32::INFINITY;
             for m in possible_moves {
                 let mut new_state = board_state.apply_move(&m);
-                let (_, eval) = self.alpha_beta(&mut new_state, depth - 1, alpha, beta, true);
+                let maximizing = new_state.player_to_move != board_state.player_to_move;
+                let (mut line, eval) =
+                    self.alpha_beta(&mut new_state, depth - 1, alpha, beta, maximizing);
                 if eval < min_eval {
                     min_eval = eval;
-                    best_move = Some(m.clone());
+                    line.insert(0, m);
+                    best_line = line;
                 }
                 beta = beta.min(eval);
                 if beta <= alpha {
@@ -70,8 +132,49 @@ impl LookAheadSolver {
                 }
             }
 
-            (best_move, min_eval)
+            (best_line, min_eval)
+        }
+    }
+
+    /// The best `count` root moves with their exact scores and principal variations,
+    /// leaving out those that score more than `margin` below the best. Each move is searched
+    /// with the lowest score it could still make the list with as its lower bound, so moves
+    /// that cannot make it are cut off as they would be in `solve`.
+    fn top_moves(&self, board_state: &BoardState, count: usize, margin: f32) -> Vec<MoveAnalysis> {
+        let depth = self.depth.saturating_sub(1);
+        let mut kept: Vec<MoveAnalysis> = Vec::new();
+        for best_move in board_state.get_possible_moves() {
+            let mut state = board_state.apply_move(&best_move);
+            let maximizing = state.player_to_move == board_state.player_to_move;
+            let worst = match kept.get(count - 1) {
+                Some(worst) => worst.score,
+                None => f32::NEG_INFINITY,
+            };
+            let alpha = kept
+                .first()
+                .map_or(worst, |best| worst.max(best.score - margin));
+            let (line, score) =
+                self.alpha_beta(&mut state, depth, alpha, f32::INFINITY, maximizing);
+            if score <= alpha && alpha > f32::NEG_INFINITY {
+                continue;
+            }
+            let at = kept.partition_point(|analysis| analysis.score >= score);
+            let mut variation = vec![best_move.clone()];
+            variation.extend(line);
+            kept.insert(
+                at,
+                MoveAnalysis {
+                    best_move,
+                    score,
+                    variation,
+                },
+            );
+            kept.truncate(count);
         }
+        if let Some(best) = kept.first().map(|best| best.score) {
+            kept.retain(|analysis| analysis.score >= best - margin);
+        }
+        kept
     }
 
     /// Delegates the evaluation to the wrapped solver.
@@ -82,10 +185,37 @@ impl LookAheadSolver {
 
 impl Solver for LookAheadSolver {
     fn solve(&self, board_state: &mut BoardState) -> (Option<BestMove>, f32) {
+        if self.multi_pv > 1 || self.variety > 0.0 {
+            let variations = match self.multi_pv {
+                1 => self.top_moves(board_state, usize::MAX, self.variety),
+                count => self.top_moves(board_state, count, f32::INFINITY),
+            };
+            let best = match variations.first() {
+                Some(best) => best.score,
+                None => return (None, self.evaluate(board_state)),
+            };
+            // Without a margin, ties go to the first best move as they do without multi-PV.
+            let candidates = if self.variety > 0.0 {
+                variations
+                    .iter()
+                    .take_while(|analysis| analysis.score >= best - self.variety)
+                    .count()
+            } else {
+                1
+            };
+            let picked = &variations[fastrand::usize(..candidates)];
+            let picked = (Some(picked.best_move.clone()), picked.score);
+            // The lines of a turn's later stones only follow on from its first.
+            if board_state.placed_this_turn.is_empty() {
+                *self.variations.borrow_mut() = variations;
+            }
+            return picked;
+        }
+
         let alpha = f32::NEG_INFINITY;
         let beta = f32::INFINITY;
 
-        let (best_move, best_score) = self.alpha_beta(
+        let (line, best_score) = self.alpha_beta(
             board_state,
             self.depth,
             alpha,
@@ -93,17 +223,29 @@ impl Solver for LookAheadSolver {
             true, // Assuming the current player is the maximizing player
         );
 
-        (best_move, best_score)
+        (line.into_iter().next(), best_score)
+    }
+
+    /// Searches every move to the full depth with a window of its own, so that each gets
+    /// its exact score rather than the bound pruning leaves the others with.
+    fn analyse(&self, board_state: &mut BoardState) -> Vec<MoveAnalysis> {
+        self.top_moves(board_state, usize::MAX, f32::INFINITY)
+    }
+
+    fn take_variations(&self) -> Vec<MoveAnalysis> {
+        self.variations.take()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use crate::{
         parser::{
             BestMove, Board, BoardState,
             Cell::{Playable, Played},
-            Player, TimeSetting,
+            MultiMove, Player, Rules, StonesPerTurn, TimeSetting,
         },
         scorer::{GreedyScorer, Scorer},
         solver::Solver,
@@ -128,5 +270,255 @@ mod tests {
         assert_eq!(mve, Some(BestMove::new(0, 2)));
     }
 
+    #[test]
+    fn should_win_by_stacking_with_gravity() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Playable, Playable, Playable, Playable],
+                vec![Playable, Playable, Playable, Playable],
+                vec![Played(Player::X), Playable, Playable, Playable],
+                vec![
+                    Played(Player::X),
+                    Played(Player::O),
+                    Played(Player::O),
+                    Playable,
+                ],
+            ]),
+            TimeSetting::Infinite,
+            Some(3),
+        )
+        .with_rules(Rules {
+            gravity: true,
+            ..Rules::default()
+        });
+        let (mve, _) =
+            LookAheadSolver::new(Box::new(GreedyScorer::default()), 2).solve(&mut board_state);
+        assert_eq!(mve, Some(BestMove::new(1, 0)));
+    }
+
     // w / max((w - s), 1)
+
+    #[test]
+    fn should_not_complete_a_line_under_misere() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Played(Player::X), Played(Player::X), Playable],
+                vec![Played(Player::O), Playable, Played(Player::O)],
+                vec![Playable, Played(Player::O), Played(Player::X)],
+            ]),
+            TimeSetting::Infinite,
+            Some(3),
+        )
+        .with_rules(Rules {
+            misere: true,
+            ..Rules::default()
+        });
+        let (mve, _) =
+            LookAheadSolver::new(Box::new(GreedyScorer::default()), 1).solve(&mut board_state);
+        assert_eq!(mve, Some(BestMove::new(2, 0)));
+    }
+
+    #[test]
+    fn should_place_both_stones_of_a_winning_pair() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![
+                    Played(Player::X),
+                    Played(Player::X),
+                    Playable,
+                    Playable,
+                    Playable,
+                ],
+                vec![Playable, Playable, Playable, Playable, Playable],
+                vec![Played(Player::O), Playable, Playable, Playable, Playable],
+                vec![Playable, Playable, Playable, Playable, Played(Player::O)],
+                vec![Playable, Playable, Playable, Playable, Playable],
+            ]),
+            TimeSetting::Infinite,
+            Some(4),
+        )
+        .with_rules(Rules {
+            stones_per_turn: StonesPerTurn { first: 1, rest: 2 },
+            ..Rules::default()
+        });
+        let (turn, _) =
+            LookAheadSolver::new(Box::new(GreedyScorer::default()), 2).solve_turn(&mut board_state);
+
+        let mut stones = turn.unwrap().moves().to_vec();
+        stones.sort_by_key(|stone| (stone.row(), stone.column()));
+        assert_eq!(stones, vec![BestMove::new(0, 2), BestMove::new(0, 3)]);
+    }
+
+    #[test]
+    fn should_place_one_stone_per_turn_by_default() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Playable, Playable, Playable],
+                vec![Played(Player::X), Played(Player::X), Played(Player::O)],
+                vec![Playable, Playable, Played(Player::O)],
+            ]),
+            TimeSetting::Infinite,
+            None,
+        );
+        let (turn, _) =
+            LookAheadSolver::new(Box::new(GreedyScorer::default()), 2).solve_turn(&mut board_state);
+        assert_eq!(turn, Some(MultiMove::from(BestMove::new(0, 2))));
+    }
+
+    #[test]
+    fn should_win_through_the_layers_of_a_cube() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::from_str("o_o/_x_/3_|3_/_x_/3_|3_/3_/3_").unwrap(),
+            TimeSetting::Infinite,
+            None,
+        );
+        let (mve, _) =
+            LookAheadSolver::new(Box::new(GreedyScorer::default()), 2).solve(&mut board_state);
+        assert_eq!(mve, Some(BestMove::new(7, 1)));
+    }
+
+    #[test]
+    fn should_count_the_positions_it_searches() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::from_str("3_/3_/3_").unwrap(),
+            TimeSetting::Infinite,
+            None,
+        );
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 1);
+        solver.solve(&mut board_state);
+        assert_eq!(solver.nodes(), 10);
+    }
+
+    #[test]
+    fn should_analyse_every_move_with_its_line_of_play() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::from_str("4_/_x2_/2_o_/4_").unwrap(),
+            TimeSetting::Infinite,
+            Some(3),
+        );
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 3);
+        let analyses = solver.analyse(&mut board_state.clone());
+        assert_eq!(analyses.len(), board_state.get_possible_moves().len());
+        assert_eq!(
+            Some(analyses[0].best_move.clone()),
+            solver.solve(&mut board_state).0
+        );
+        assert!(analyses
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+        for analysis in analyses {
+            assert_eq!(analysis.variation[0], analysis.best_move);
+            assert_eq!(analysis.variation.len(), 3);
+        }
+    }
+
+    #[test]
+    fn should_end_each_line_of_play_in_the_position_its_score_comes_from() {
+        let board_state = BoardState::new(
+            Player::X,
+            Board::from_str("4_/_x2_/2_o_/4_").unwrap(),
+            TimeSetting::Infinite,
+            Some(3),
+        );
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 3);
+        for analysis in solver.analyse(&mut board_state.clone()) {
+            let mut state = board_state.clone();
+            for mv in &analysis.variation {
+                state = state.apply_move(mv);
+            }
+            assert_eq!(GreedyScorer::default().score(&mut state), analysis.score);
+        }
+    }
+
+    #[test]
+    fn should_keep_the_best_variations_of_a_multi_pv_search() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::from_str("5_/5_/2_x2_/2_o2_/5_").unwrap(),
+            TimeSetting::Infinite,
+            Some(3),
+        );
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 2);
+        let analyses = solver.analyse(&mut board_state.clone());
+        let solver = solver.with_multi_pv(3);
+        let (mve, score) = solver.solve(&mut board_state);
+        let variations = solver.take_variations();
+        assert_eq!(variations.len(), 3);
+        assert_eq!(mve, Some(variations[0].best_move.clone()));
+        assert_eq!(score, analyses[0].score);
+        let scores = variations.iter().map(|v| v.score).collect::<Vec<_>>();
+        let expected = analyses[..3].iter().map(|a| a.score).collect::<Vec<_>>();
+        assert_eq!(scores, expected);
+        assert!(solver.take_variations().is_empty());
+    }
+
+    #[test]
+    fn should_only_vary_between_moves_within_the_margin() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::new(vec![
+                vec![Playable, Playable, Playable],
+                vec![Played(Player::X), Played(Player::X), Played(Player::O)],
+                vec![Playable, Playable, Played(Player::O)],
+            ]),
+            TimeSetting::Infinite,
+            None,
+        );
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 2)
+            .with_multi_pv(4)
+            .with_variety(1.0);
+        for _ in 0..10 {
+            let (mve, _) = solver.solve(&mut board_state);
+            assert_eq!(mve, Some(BestMove::new(0, 2)));
+        }
+    }
+
+    #[test]
+    fn should_vary_between_equal_moves_without_multi_pv() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::from_str("3_/3_/3_").unwrap(),
+            TimeSetting::Infinite,
+            None,
+        );
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 1).with_variety(0.5);
+        let mut picks = Vec::new();
+        for _ in 0..30 {
+            let mve = solver.solve(&mut board_state).0.unwrap();
+            if !picks.contains(&mve) {
+                picks.push(mve);
+            }
+        }
+        assert!(picks.len() > 1);
+        let candidates = solver.take_variations();
+        assert!(picks
+            .iter()
+            .all(|mve| candidates.iter().any(|analysis| analysis.best_move == *mve)));
+    }
+
+    #[test]
+    fn should_report_the_lines_of_a_turn_from_its_first_stone() {
+        let mut board_state = BoardState::new(
+            Player::X,
+            Board::from_str("5_/5_/2_x2_/2_o2_/5_").unwrap(),
+            TimeSetting::Infinite,
+            Some(4),
+        )
+        .with_rules(Rules {
+            stones_per_turn: StonesPerTurn { first: 1, rest: 2 },
+            ..Rules::default()
+        });
+        let solver = LookAheadSolver::new(Box::new(GreedyScorer::default()), 2).with_multi_pv(3);
+        let (turn, _) = solver.solve_turn(&mut board_state);
+        let variations = solver.take_variations();
+        assert_eq!(variations.len(), 3);
+        assert_eq!(variations[0].best_move, turn.unwrap().moves()[0]);
+    }
 }
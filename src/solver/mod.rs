@@ -1,4 +1,6 @@
-use crate::parser::{BestMove, BoardState};
+use std::fmt::Display;
+
+use crate::parser::{BestMove, BoardState, MultiMove};
 
 mod first_move_solver;
 mod greedy_solver;
@@ -7,6 +9,78 @@ mod look_ahead_solver;
 pub use greedy_solver::GreedySolver;
 pub use look_ahead_solver::LookAheadSolver;
 
+/// A candidate move with the score the search gives it and the moves it expects to
+/// follow, starting with the move itself. Written as e.g. `b2 score 12.5 pv b2 a1 c3`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct MoveAnalysis {
+    pub best_move: BestMove,
+    pub score: f32,
+    pub variation: Vec<BestMove>,
+}
+
+impl Display for MoveAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let notation =
+            |mv: &BestMove| format!("{}{}", mv.get_column_notation(), mv.get_row_notation());
+        let variation = self.variation.iter().map(notation).collect::<Vec<_>>();
+        write!(
+            f,
+            "{} score {} pv {}",
+            notation(&self.best_move),
+            self.score,
+            variation.join(" ")
+        )
+    }
+}
+
 pub trait Solver {
     fn solve(&self, board_state: &mut BoardState) -> (Option<BestMove>, f32);
+
+    /// Scores the legal moves, best first, each with the line of play expected to follow.
+    /// Solvers that only know their best move list just that one.
+    fn analyse(&self, board_state: &mut BoardState) -> Vec<MoveAnalysis> {
+        match self.solve(board_state) {
+            (Some(best_move), score) => vec![MoveAnalysis {
+                best_move: best_move.clone(),
+                score,
+                variation: vec![best_move],
+            }],
+            (None, _) => Vec::new(),
+        }
+    }
+
+    /// The principal variations of the moves the last search weighed, best first, left
+    /// for the caller to report once. Empty for solvers that only search for one move.
+    fn take_variations(&self) -> Vec<MoveAnalysis> {
+        Vec::new()
+    }
+
+    /// Picks every stone of the player's turn, one after the other, for rules that place
+    /// more than one stone per turn.
+    fn solve_turn(&self, board_state: &mut BoardState) -> (Option<MultiMove>, f32) {
+        let player = board_state.player_to_move;
+        let mut state = board_state.clone();
+        let mut moves = Vec::new();
+        let mut score = -1f32;
+
+        for _ in 0..board_state.stones_this_turn() {
+            if state.player_to_move != player || state.is_game_over() {
+                break;
+            }
+
+            let (best_move, best_score) = self.solve(&mut state);
+            let Some(best_move) = best_move else {
+                break;
+            };
+            state = state.apply_move(&best_move);
+            moves.push(best_move);
+            score = best_score;
+        }
+
+        if moves.is_empty() {
+            (None, -1f32)
+        } else {
+            (Some(MultiMove::new(moves)), score)
+        }
+    }
 }
@@ -0,0 +1,3 @@
+mod spsa;
+
+pub use spsa::Spsa;
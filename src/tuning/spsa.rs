@@ -0,0 +1,158 @@
+use crate::{
+    arena::{Match, MatchResult, SolverContestant},
+    evaluator::{Weights, WEIGHT_COUNT},
+    parser::BoardState,
+    scorer::GreedyScorer,
+    solver::LookAheadSolver,
+};
+
+/// Size of the first step towards the better weights, shrinking as tuning goes on.
+const STEP: f32 = 0.1;
+/// Size of the first nudge to the weights, shrinking as tuning goes on.
+const PERTURBATION: f32 = 0.2;
+/// The smallest a weight may become; a weight of 0 would switch its evaluator off for good.
+const MIN_WEIGHT: f32 = 0.05;
+
+/// Tunes evaluator weights from self-play by simultaneous perturbation stochastic
+/// approximation (SPSA): every iteration nudges all the weights at once in a random
+/// direction and in the opposite one, plays a match between the two and moves the weights
+/// towards the side that scored better.
+///
+/// Both sides search with a [`LookAheadSolver`] of `depth`, playing `games` games on each
+/// starting position.
+pub struct Spsa {
+    starts: Vec<BoardState>,
+    games: u32,
+    depth: u32,
+}
+
+impl Spsa {
+    pub fn new(starts: Vec<BoardState>, games: u32, depth: u32) -> Self {
+        Self {
+            starts,
+            games,
+            depth,
+        }
+    }
+
+    /// Runs `iterations` iterations from `weights`, reporting the weights and the match of
+    /// each iteration to `report`.
+    pub fn tune(
+        &self,
+        weights: Weights,
+        iterations: u32,
+        mut report: impl FnMut(u32, &Weights, &MatchResult),
+    ) -> Weights {
+        (0..iterations).fold(weights, |weights, iteration| {
+            let (weights, result) = self.iterate(&weights, iteration);
+            report(iteration + 1, &weights, &result);
+            weights
+        })
+    }
+
+    /// One iteration from `weights`, the `iteration`th counting from 0: the weights it
+    /// moves to and the match between the nudged weights, as seen by the positive nudge.
+    pub fn iterate(&self, weights: &Weights, iteration: u32) -> (Weights, MatchResult) {
+        let (step, perturbation) = gains(iteration);
+        let directions: [f32; WEIGHT_COUNT] =
+            std::array::from_fn(|_| if fastrand::bool() { 1.0 } else { -1.0 });
+
+        let nudged = |sign: f32| {
+            let values = weights.values();
+            Weights::from_values(std::array::from_fn(|i| {
+                (values[i] + sign * perturbation * directions[i]).max(MIN_WEIGHT)
+            }))
+        };
+        let result = Match::new(self.starts.clone(), self.games).play(
+            &mut self.contestant("plus", nudged(1.0)),
+            &mut self.contestant("minus", nudged(-1.0)),
+        );
+
+        let weights = update(weights, &directions, result.score(), step, perturbation);
+        (weights, result)
+    }
+
+    fn contestant(&self, name: &str, weights: Weights) -> SolverContestant {
+        SolverContestant::new(
+            name,
+            Box::new(LookAheadSolver::new(
+                Box::new(GreedyScorer::new(weights)),
+                self.depth,
+            )),
+        )
+    }
+}
+
+/// The step and perturbation sizes of the `iteration`th iteration, shrinking at the rates
+/// Spall recommends so that tuning settles down.
+fn gains(iteration: u32) -> (f32, f32) {
+    let k = (iteration + 1) as f32;
+    (STEP / k.powf(0.602), PERTURBATION / k.powf(0.101))
+}
+
+/// Moves `weights` along the gradient the match score of the positive nudge in
+/// `directions` estimates: a score of 1/2 leaves them where they are.
+fn update(
+    weights: &Weights,
+    directions: &[f32; WEIGHT_COUNT],
+    score: f64,
+    step: f32,
+    perturbation: f32,
+) -> Weights {
+    // The negative nudge scored 1 - score, so the difference is 2 * score - 1.
+    let difference = 2.0 * score as f32 - 1.0;
+    let values = weights.values();
+    Weights::from_values(std::array::from_fn(|i| {
+        let gradient = difference / (2.0 * perturbation * directions[i]);
+        (values[i] + step * gradient).max(MIN_WEIGHT)
+    }))
+}
+
+#[cfg(test)]
+mod test_spsa {
+    use crate::{
+        evaluator::Weights,
+        parser::{Board, BoardState, Player, TimeSetting},
+    };
+
+    use super::{update, Spsa, MIN_WEIGHT};
+
+    #[test]
+    fn moves_towards_the_winning_nudge() {
+        let weights = Weights::from_values([1.0; 5]);
+        let directions = [1.0, -1.0, 1.0, -1.0, 1.0];
+        let updated = update(&weights, &directions, 1.0, 0.2, 0.5);
+        assert_eq!(updated.values(), [1.2, 0.8, 1.2, 0.8, 1.2]);
+    }
+
+    #[test]
+    fn stays_put_after_an_even_match() {
+        let weights = Weights::default();
+        let updated = update(&weights, &[1.0; 5], 0.5, 0.2, 0.5);
+        assert_eq!(updated, weights);
+    }
+
+    #[test]
+    fn keeps_weights_above_the_minimum() {
+        let weights = Weights::from_values([0.1; 5]);
+        let updated = update(&weights, &[1.0; 5], 0.0, 1.0, 0.5);
+        assert_eq!(updated.values(), [MIN_WEIGHT; 5]);
+    }
+
+    #[test]
+    fn tunes_from_self_play() {
+        let start = BoardState::new(
+            Player::X,
+            "3_/3_/3_".parse::<Board>().unwrap(),
+            TimeSetting::Infinite,
+            None,
+        );
+        let mut reports = 0;
+        let weights = Spsa::new(vec![start], 2, 1).tune(Weights::default(), 2, |_, _, result| {
+            assert_eq!(result.games(), 2);
+            reports += 1;
+        });
+        assert_eq!(reports, 2);
+        assert!(weights.values().iter().all(|value| *value >= MIN_WEIGHT));
+    }
+}
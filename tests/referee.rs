@@ -0,0 +1,60 @@
+use t3elric_engine::{
+    arena::{EngineContestant, Referee},
+    cli::start_position,
+    parser::{Player, Rules, TimeSetting},
+    record::{GameEnd, GameRecord},
+};
+
+const ENGINE: &str = env!("CARGO_BIN_EXE_t3elric-engine");
+
+fn engine(args: &[&str]) -> EngineContestant {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    EngineContestant::start(ENGINE, &args).unwrap()
+}
+
+#[test]
+fn plays_a_game_between_two_engines_within_the_clock() {
+    let mut start = start_position("3x3", &Rules::default()).unwrap();
+    start.time_setting = "time-remaining s:30 increment s:1"
+        .parse::<TimeSetting>()
+        .unwrap();
+
+    let record = Referee::new(start).play(&mut engine(&[]), &mut engine(&[]));
+
+    assert!(
+        !matches!(record.end, GameEnd::Forfeited(..)),
+        "{:?}",
+        record.end
+    );
+    assert!(record.turns.len() >= 5, "{}", record);
+    assert_eq!(GameRecord::read_all(&record.to_string()), Ok(vec![record]));
+}
+
+#[test]
+fn plays_by_the_engines_rules() {
+    let rules = Rules::from_args(vec!["--gravity".to_string()]).unwrap();
+    let start = start_position("6x7:4", &rules).unwrap();
+
+    let record = Referee::new(start).play(&mut engine(&["--gravity"]), &mut engine(&["--gravity"]));
+
+    assert!(
+        !matches!(record.end, GameEnd::Forfeited(..)),
+        "{:?}",
+        record.end
+    );
+}
+
+#[test]
+fn forfeits_an_engine_that_does_not_shake_hands() {
+    let start = start_position("3x3", &Rules::default()).unwrap();
+    let mut silent = EngineContestant::start("true", &[]).unwrap();
+
+    let record = Referee::new(start).play(&mut silent, &mut engine(&[]));
+
+    assert!(
+        matches!(record.end, GameEnd::Forfeited(Player::X, _)),
+        "{:?}",
+        record.end
+    );
+    assert!(record.turns.is_empty());
+}